@@ -128,6 +128,17 @@ pub mod util {
         entity: Entity,
         asset_name: &str,
         storages: &mut AssetStorages,
+    ) {
+        insert_into_asset_storages_with_texture(entity, asset_name, asset_name, storages);
+    }
+
+    /// Attaches the `MeshHandle` of one asset and the texture of an other asset to the entity.
+    /// This way, one mesh can be shown with different textures.
+    pub fn insert_into_asset_storages_with_texture(
+        entity: Entity,
+        mesh_name: &str,
+        texture_name: &str,
+        storages: &mut AssetStorages,
     ) {
         let (
             ref loader,
@@ -142,7 +153,7 @@ pub mod util {
 
         let mesh = {
             mesh_manager.get_asset_handle_or_load(
-                asset_name,
+                mesh_name,
                 ObjFormat,
                 Default::default(),
                 mesh_storage,
@@ -153,7 +164,7 @@ pub mod util {
         // load texture/material
         let material = {
             let handle = tex_manager.get_asset_handle_or_load(
-                asset_name,
+                texture_name,
                 PngFormat,
                 TextureMetadata::srgb(),
                 tex_storage,
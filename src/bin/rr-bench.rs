@@ -0,0 +1,209 @@
+//! Benchmarks for code that runs on every `Tile` of a cave or for many `RockRaider`s at once.
+//!
+//! usage: `cargo run --release --bin rr-bench`
+//!
+//! The benchmarks run on generated 256x256 caves, so they do not depend on the levels in `assets/levels/`.
+
+extern crate amethyst;
+extern crate pathfinding;
+extern crate rand;
+extern crate rock_raiders;
+
+use amethyst::config::Config;
+use pathfinding::directed::bfs;
+use rand::{rngs::StdRng, Rng, SeedableRng};
+use rock_raiders::{
+    entities::Tile,
+    level::{
+        find_grid_path, find_sprite, neighborhood_of, FlowField, TileLookupTable, TilePatternMap,
+    },
+};
+
+use std::time::{Duration, Instant};
+
+const CAVE_SIZE: usize = 256;
+const ITERATIONS: u32 = 20;
+/// The number of `RockRaider`s heading to the same destination
+const RAIDERS: usize = 50;
+
+fn main() {
+    let tile_patterns = TilePatternMap::load(format!(
+        "{}/resources/tile_config.ron",
+        env!("CARGO_MANIFEST_DIR")
+    ));
+    let cave = generate_cave(CAVE_SIZE);
+
+    bench_sprite_selection(&cave, &tile_patterns);
+    bench_path_search(&generate_open_cave(CAVE_SIZE));
+}
+
+/// Creates a random cave, that contains walls, concealed and revealed ground, water, lava and rubble.
+fn generate_cave(size: usize) -> Vec<Vec<Tile>> {
+    let mut rng = StdRng::seed_from_u64(22);
+    (0..size)
+        .map(|_| {
+            (0..size)
+                .map(|_| match rng.gen_range(0, 6) {
+                    0 => Tile::default(),
+                    1 => Tile::Ground { concealed: true },
+                    2 => Tile::Water,
+                    3 => Tile::Lava,
+                    4 => Tile::Rubble { level: 2 },
+                    _ => Tile::Ground { concealed: false },
+                })
+                .collect()
+        })
+        .collect()
+}
+
+/// Creates a random cave of revealed ground with some walls and rubble in it, so that most of the ground is connected.
+fn generate_open_cave(size: usize) -> Vec<Vec<Tile>> {
+    let mut rng = StdRng::seed_from_u64(23);
+    (0..size)
+        .map(|_| {
+            (0..size)
+                .map(|_| match rng.gen_range(0, 10) {
+                    0 | 1 => Tile::default(),
+                    2 => Tile::Rubble { level: 2 },
+                    _ => Tile::Ground { concealed: false },
+                })
+                .collect()
+        })
+        .collect()
+}
+
+fn tile_at(cave: &Vec<Vec<Tile>>, x: i32, y: i32) -> Option<Tile> {
+    if x < 0 || y < 0 {
+        return None;
+    }
+    cave.get(x as usize)
+        .and_then(|column| column.get(y as usize))
+        .cloned()
+}
+
+/// Runs `f` `ITERATIONS` times and prints the average duration.
+/// `f` returns some number derived from its work, so the optimizer cannot drop the work completely.
+fn bench<F: FnMut() -> usize>(name: &str, mut f: F) {
+    let start = Instant::now();
+    let mut checksum = 0;
+    for _ in 0..ITERATIONS {
+        checksum += f();
+    }
+    let average = start.elapsed() / ITERATIONS;
+    println!(
+        "{:<40} {:>10.3} ms (checksum {})",
+        name,
+        as_millis(average),
+        checksum
+    );
+}
+
+fn as_millis(duration: Duration) -> f64 {
+    duration.as_secs() as f64 * 1000. + f64::from(duration.subsec_nanos()) / 1_000_000.
+}
+
+/// Compares searching the `TilePatternMap` with the `TileLookupTable` for every `Tile` of the cave.
+/// That both come to the same result is tested in `level::tile_pattern`.
+fn bench_sprite_selection(cave: &Vec<Vec<Tile>>, tile_patterns: &TilePatternMap) {
+    let lookup_table = TileLookupTable::new(tile_patterns);
+
+    bench("compile TileLookupTable", || {
+        TileLookupTable::new(tile_patterns)
+            .lookup(&[[Tile::default(); 3]; 3])
+            .is_some() as usize
+    });
+    bench("sprite selection: TilePatternMap search", || {
+        let mut found = 0;
+        for x in 0..cave.len() as i32 {
+            for y in 0..cave[0].len() as i32 {
+                let neighborhood = neighborhood_of(x, y, |x, y| tile_at(cave, x, y));
+                if find_sprite(&neighborhood, tile_patterns).is_some() {
+                    found += 1;
+                }
+            }
+        }
+        found
+    });
+    bench("sprite selection: TileLookupTable", || {
+        let mut found = 0;
+        for x in 0..cave.len() as i32 {
+            for y in 0..cave[0].len() as i32 {
+                let neighborhood = neighborhood_of(x, y, |x, y| tile_at(cave, x, y));
+                if lookup_table.lookup(&neighborhood).is_some() {
+                    found += 1;
+                }
+            }
+        }
+        found
+    });
+}
+
+/// Compares searching a path for every `RockRaider` with following a single `FlowField`, when all of them head to the middle of the cave.
+fn bench_path_search(cave: &Vec<Vec<Tile>>) {
+    let size = cave.len();
+    let movement_cost = |_: (i32, i32), (x, y): (i32, i32)| {
+        tile_at(cave, x, y).and_then(|tile| tile.movement_cost())
+    };
+    let destination = (size as i32 / 2, size as i32 / 2);
+
+    let field = FlowField::new(&[destination], size, size, &movement_cost);
+    let mut rng = StdRng::seed_from_u64(24);
+    let mut raiders = Vec::with_capacity(RAIDERS);
+    while raiders.len() < RAIDERS {
+        let position = (rng.gen_range(0, size as i32), rng.gen_range(0, size as i32));
+        if field.cost(position.0, position.1).is_some() {
+            raiders.push(position);
+        }
+    }
+
+    // both have to find equally cheap paths, otherwise the comparison is worthless
+    for &(x, y) in raiders.iter() {
+        assert_eq!(
+            find_grid_path((x, y), &[destination], &movement_cost).map(|(_, cost)| cost),
+            field.cost(x, y),
+            "path search differs at ({}, {})",
+            x,
+            y
+        );
+    }
+
+    bench("path search: BFS per raider", || {
+        raiders
+            .iter()
+            .filter_map(|start| {
+                bfs::bfs(
+                    start,
+                    |&(x, y)| {
+                        vec![(x + 1, y), (x - 1, y), (x, y + 1), (x, y - 1)]
+                            .into_iter()
+                            .filter(move |&neighbor| movement_cost((x, y), neighbor).is_some())
+                    },
+                    |&position| position == destination,
+                )
+            })
+            .map(|path| path.len())
+            .sum()
+    });
+    bench("path search: A* per raider", || {
+        raiders
+            .iter()
+            .filter_map(|&start| find_grid_path(start, &[destination], &movement_cost))
+            .map(|(path, _)| path.len())
+            .sum()
+    });
+    bench("path search: one FlowField", || {
+        let field = FlowField::new(&[destination], size, size, &movement_cost);
+        raiders
+            .iter()
+            .map(|&(mut x, mut y)| {
+                let mut steps = 1;
+                while let Some(next_step) = field.next_step(x, y) {
+                    x = next_step.0;
+                    y = next_step.1;
+                    steps += 1;
+                }
+                steps
+            })
+            .sum()
+    });
+}
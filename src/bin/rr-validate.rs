@@ -0,0 +1,72 @@
+//! Checks level files for problems that would otherwise only show up while playing them.
+//!
+//! usage: `rr-validate [<level.ron>...]`
+//!
+//! Before any level is checked, every possible neighborhood of tiles is tested against `resources/tile_config.ron`, so that gaps in the patterns are found even if no level uses them yet.
+//! Every problem is printed together with the position of the `Tile` it was found at.
+//! The process exits with a non-zero status if the patterns or any level have a problem.
+
+extern crate amethyst;
+extern crate rock_raiders;
+
+use amethyst::config::Config;
+use rock_raiders::level::{check_coverage, validate_level, LevelDocument, TilePatternMap};
+
+use std::{env, path::Path, process};
+
+fn main() {
+    let levels: Vec<String> = env::args().skip(1).collect();
+
+    let config_path = format!("{}/resources/tile_config.ron", env!("CARGO_MANIFEST_DIR"));
+    let tile_patterns = match TilePatternMap::load_no_fallback(&config_path) {
+        Ok(tile_patterns) => tile_patterns,
+        Err(error) => {
+            eprintln!("{}: cannot load tile patterns: {}", config_path, error);
+            process::exit(2);
+        }
+    };
+    let assets_dir = format!("{}/assets", env!("CARGO_MANIFEST_DIR"));
+
+    let coverage = check_coverage(&tile_patterns);
+    for neighborhood in coverage.uncovered.iter() {
+        eprintln!("{}: no pattern matches {:?}", config_path, neighborhood);
+    }
+    for (neighborhood, assets) in coverage.ambiguous.iter() {
+        eprintln!(
+            "{}: {:?} is matched by several assets: {}",
+            config_path,
+            neighborhood,
+            assets.join(", ")
+        );
+    }
+    let mut failed = !coverage.is_complete();
+    if !failed {
+        println!("{}: ok", config_path);
+    }
+
+    for level in levels.iter() {
+        let document = match LevelDocument::load_from(Path::new(level)) {
+            Ok(document) => document,
+            Err(error) => {
+                eprintln!("{}: cannot load level: {}", level, error);
+                failed = true;
+                continue;
+            }
+        };
+
+        let problems = validate_level(&document, &tile_patterns, Path::new(&assets_dir));
+        for problem in problems.iter() {
+            eprintln!("{}: {}", level, problem);
+        }
+
+        if problems.is_empty() {
+            println!("{}: ok", level);
+        } else {
+            failed = true;
+        }
+    }
+
+    if failed {
+        process::exit(1);
+    }
+}
@@ -2,9 +2,9 @@ use amethyst::{
     assets::{AssetStorage, Loader},
     core::{
         nalgebra::{Point2, Vector3},
-        transform::{GlobalTransform, Parent, ParentHierarchy, Transform},
+        transform::{GlobalTransform, Parent, Transform},
     },
-    ecs::prelude::{Builder, Component, Entity, NullStorage, World},
+    ecs::prelude::{Component, Entity, Join, NullStorage, World},
     renderer::{PngFormat, Texture, TextureMetadata},
 };
 
@@ -13,30 +13,78 @@ use rand::prelude::*;
 use assetmanagement::{util::insert_into_asset_storages, AssetManager};
 use entities::{RockRaider, Tile};
 use eventhandling::{ClickHandlerComponent, Clickable, HoverHandlerComponent, SimpleHoverHandler};
-use level::LevelGrid;
+use level::{LevelGrid, LevelInfo};
+use systems::{OreStockpile, Path, SpawnQueue, MAX_RAIDERS};
 use util::amount_in;
 
 use ncollide3d::shape::Cuboid;
 
-const MAX_RAIDERS: usize = 10;
+use std::fmt;
 
 /// A tag to indicate the entity as `Base`
 /// The `Base` is the main building of each Level with which you start with and where you can spawn additional `RockRaider`
 pub struct Base;
 
+/// The reasons, why a `Base` refuses to queue another `RockRaider`
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum SpawnError {
+    /// There are `MAX_RAIDERS` in the level already, counting the queued ones
+    RaiderLimit,
+    /// The `OreStockpile` holds less ore than a `RockRaider` costs. See `LevelInfo::raider_cost`
+    NotEnoughResources { needed: u32, available: u32 },
+}
+
+impl fmt::Display for SpawnError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            SpawnError::RaiderLimit => write!(
+                f,
+                "the limit of {} Rock Raiders is reached already",
+                MAX_RAIDERS
+            ),
+            SpawnError::NotEnoughResources { needed, available } => write!(
+                f,
+                "it costs {} ore, but only {} are in the stockpile",
+                needed, available
+            ),
+        }
+    }
+}
+
 impl Base {
-    pub fn spawn_rock_raider(&self, own_entity: Entity, world: &World) -> Entity {
-        {
-            let rr_storage = world.read_storage::<RockRaider>();
-            if amount_in(&rr_storage) >= MAX_RAIDERS {
-                panic!(
-                    "Cannot spawn more Raiders. Limit of {} is already reached",
-                    MAX_RAIDERS
-                );
-            }
+    /// Pays for another `RockRaider` and adds it to the `SpawnQueue` of the `Base`. Returns the length of the queue or the reason, why the `RockRaider` cannot be queued.
+    pub fn queue_rock_raider(own_entity: Entity, world: &World) -> Result<u32, SpawnError> {
+        let queued: u32 = world
+            .read_storage::<SpawnQueue>()
+            .join()
+            .map(|queue| queue.queued)
+            .sum();
+        if amount_in(&world.read_storage::<RockRaider>()) + queued as usize >= MAX_RAIDERS {
+            return Err(SpawnError::RaiderLimit);
         }
 
-        let spawn_position = {
+        let cost = world.read_resource::<LevelInfo>().raider_cost;
+        let mut stockpile = world.write_resource::<OreStockpile>();
+        if stockpile.ore < cost {
+            return Err(SpawnError::NotEnoughResources {
+                needed: cost,
+                available: stockpile.ore,
+            });
+        }
+        stockpile.ore -= cost;
+
+        let mut queues = world.write_storage::<SpawnQueue>();
+        let queue = queues.get_mut(own_entity).unwrap();
+        queue.queued += 1;
+        Ok(queue.queued)
+    }
+
+    /// Spawns a `RockRaider` on a random `Tile` next to the `Base` and sends it to the rally point of the `SpawnQueue`, if there is one.
+    /// Returns `None`, if there is no `Tile` next to the `Base`, that can be walked on.
+    ///
+    /// This does not take the `RockRaider` from the `SpawnQueue`.
+    pub fn spawn_rock_raider(own_entity: Entity, world: &World) -> Option<Entity> {
+        let spawn_tile = {
             let parent = world
                 .read_storage::<Parent>()
                 .get(own_entity)
@@ -46,60 +94,87 @@ impl Base {
             let level_grid = world.read_resource::<LevelGrid>();
             let transforms = world.read_storage::<Transform>();
             let possible_spawns = level_grid.walkable_neighbors(&parent, &tiles, &transforms);
-
-            // when the spawns are empty, something went horribly wrong
-            assert!(!possible_spawns.is_empty());
+            if possible_spawns.is_empty() {
+                return None;
+            }
 
             let spawn_index = ((rand::thread_rng().gen::<f32>() * possible_spawns.len() as f32)
                 .floor() as usize)
                 // in case `thread_rng` returned exactly 1, we need to subtract 1 in the end
                 .min(possible_spawns.len() - 1);
-            let spawn_tile_position = transforms
-                .get(possible_spawns[spawn_index])
-                .unwrap()
-                .translation();
+            possible_spawns[spawn_index]
+        };
+        let spawn_position = {
+            let transforms = world.read_storage::<Transform>();
+            let spawn_tile_position = transforms.get(spawn_tile).unwrap().translation();
             Point2::new(spawn_tile_position.x, spawn_tile_position.z)
         };
 
-        let storages = world.system_data();
-        let hover_storage = world.system_data();
-        let click_storage = world.system_data();
-        let entities = world.entities();
-        RockRaider::instantiate(
-            &entities,
-            spawn_position,
-            storages,
-            hover_storage,
-            click_storage,
-        )
-    }
+        let rock_raider = {
+            let storages = world.system_data();
+            let hover_storage = world.system_data();
+            let click_storage = world.system_data();
+            let entities = world.entities();
+            RockRaider::instantiate(
+                &entities,
+                spawn_position,
+                storages,
+                hover_storage,
+                click_storage,
+            )
+        };
 
-    /// Create a new Base. The given entity has to have a `Tile::Ground` Component, which then is used as Parent to determine the Position
-    pub fn build(entity: &Entity, world: &mut World) {
-        {
-            // if the entity has children, they have to be buildings so far.
-            // If we will ever have other children than buildings, we will have to test for every children, if it is a building
-            let hierarchy = world.read_resource::<ParentHierarchy>();
-            if !hierarchy.children(*entity).is_empty() {
-                panic!("ERROR another building occupies this tile");
+        let rally_point = world
+            .read_storage::<SpawnQueue>()
+            .get(own_entity)
+            .and_then(|queue| queue.rally_point);
+        if let Some(rally_point) = rally_point {
+            let path = world.read_resource::<LevelGrid>().find_path_to_nearest(
+                spawn_tile,
+                &[rally_point],
+                &world.read_storage::<Tile>(),
+                &world.read_storage::<Transform>(),
+            );
+            match path {
+                Some(path) => {
+                    world
+                        .write_storage::<Path>()
+                        .insert(rock_raider, path)
+                        .unwrap();
+                }
+                None => warn!(
+                    "The rally point ({}, {}) cannot be reached",
+                    rally_point.0, rally_point.1
+                ),
             }
         }
+        Some(rock_raider)
+    }
 
-        // if the tile is a revealed ground tile, we continue, otherwise we return
-        match world.read_storage::<Tile>().get(*entity) {
-            Some(Tile::Ground { .. }) => (),
-
-            _ => panic!("ERROR cannot build on Walls"),
-        }
-
-        let base = Base::default();
-
+    /// Creates a new `Base` on the `Tile`, which is used as its `Parent` to determine the position.
+    /// This neither checks, whether the `Base` may stand there, nor blocks the `Tile`s it covers. Use `BuildingType::build` or `place_building` for that.
+    pub fn build(tile: &Entity, world: &World) -> Entity {
         let result = world
-            .create_entity()
-            .with(Transform::default()) // since we will be a child, this should translate us to the perfect spot ;)
-            .with(GlobalTransform::default())
-            .with(base)
-            .with(Parent { entity: *entity })
+            .entities()
+            .build_entity()
+            // since we will be a child, this should translate us to the perfect spot ;)
+            .with(
+                Transform::default(),
+                &mut world.write_storage::<Transform>(),
+            )
+            .with(
+                GlobalTransform::default(),
+                &mut world.write_storage::<GlobalTransform>(),
+            )
+            .with(Base::default(), &mut world.write_storage::<Base>())
+            .with(
+                SpawnQueue::default(),
+                &mut world.write_storage::<SpawnQueue>(),
+            )
+            .with(
+                Parent { entity: *tile },
+                &mut world.write_storage::<Parent>(),
+            )
             .build();
 
         {
@@ -120,9 +195,11 @@ impl Base {
         click_storage
             .insert(result, Self::new_click_handler())
             .unwrap();
+
+        result
     }
 
-    fn asset_name() -> &'static str {
+    pub fn asset_name() -> &'static str {
         "buildings/base"
     }
 
@@ -160,6 +237,9 @@ impl Default for Base {
 
 impl Clickable for Base {
     fn on_click(&self, entity: Entity, world: &World) {
-        self.spawn_rock_raider(entity, world);
+        match Base::queue_rock_raider(entity, world) {
+            Ok(queued) => debug!("{} Rock Raiders queued", queued),
+            Err(error) => warn!("Cannot spawn a Rock Raider: {}", error),
+        }
     }
 }
@@ -0,0 +1,87 @@
+use amethyst::{
+    core::transform::{GlobalTransform, Parent, Transform},
+    ecs::prelude::{Component, DenseVecStorage, Entity, World},
+};
+
+use assetmanagement::util::insert_into_asset_storages;
+use entities::buildings::{BuildingType, Facing};
+use level::LevelGrid;
+
+/// A building, that was placed by the player, but is not built yet.
+///
+/// Its ore is taken from the `OreStockpile` when it is placed. The `RockRaider`s bring that ore to the site and work on it, until it turns into the building. See `ConstructionSystem`
+/// The site only holds plain data, so it can be stored and restored as it is.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct ConstructionSite {
+    pub building: BuildingType,
+    pub facing: Facing,
+    /// The grid position of the building's front `Tile`
+    pub position: (i32, i32),
+    /// The ore, that was taken from the `OreStockpile` for the site and waits at the `Base` to be fetched
+    pub reserved_ore: u32,
+    /// The ore, that was brought to the site so far
+    pub delivered_ore: u32,
+    /// The seconds of work, that are left, once all ore is delivered
+    pub remaining_work: f32,
+}
+
+impl ConstructionSite {
+    /// Creates a new `ConstructionSite` for the building with its front onto the `Tile` at (x, y), which is used as its `Parent` to determine the position.
+    /// This does not check, whether the building may stand there, and does not take the ore from the `OreStockpile`. Use `place_building` for that.
+    pub fn build(building: BuildingType, x: i32, y: i32, facing: Facing, world: &World) -> Entity {
+        let tile = world.read_resource::<LevelGrid>().get(x, y).unwrap();
+        let site = ConstructionSite {
+            building,
+            facing,
+            position: (x, y),
+            reserved_ore: building.cost(),
+            delivered_ore: 0,
+            remaining_work: building.build_time(),
+        };
+
+        let result = world
+            .entities()
+            .build_entity()
+            .with(
+                Transform::default(),
+                &mut world.write_storage::<Transform>(),
+            )
+            .with(
+                GlobalTransform::default(),
+                &mut world.write_storage::<GlobalTransform>(),
+            )
+            .with(site, &mut world.write_storage::<ConstructionSite>())
+            .with(
+                Parent { entity: tile },
+                &mut world.write_storage::<Parent>(),
+            )
+            .build();
+
+        {
+            let mut storages = world.system_data();
+            insert_into_asset_storages(result, ConstructionSite::asset_name(), &mut storages);
+        }
+
+        // the site blocks the `Tile`s of the building right away, so nothing else is placed there in the meantime
+        building.occupy(result, x, y, facing, world);
+        result
+    }
+
+    pub fn asset_name() -> &'static str {
+        "buildings/construction_site"
+    }
+
+    /// The ore, that still has to be brought to the site
+    pub fn missing_ore(&self) -> u32 {
+        self.building.cost().saturating_sub(self.delivered_ore)
+    }
+
+    /// Whether the site can be turned into its building
+    pub fn is_finished(&self) -> bool {
+        self.missing_ore() == 0 && self.remaining_work <= 0.
+    }
+}
+
+impl Component for ConstructionSite {
+    type Storage = DenseVecStorage<Self>;
+}
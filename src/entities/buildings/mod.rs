@@ -1,3 +1,12 @@
 mod base;
+mod construction_site;
+mod placement;
+mod support_station;
 
-pub use self::base::Base;
+pub use self::base::{Base, SpawnError};
+pub use self::construction_site::ConstructionSite;
+pub use self::placement::{
+    check_footprint, check_placement, check_site, check_start_footprint, place_building,
+    BuildingType, Facing, PlacementError,
+};
+pub use self::support_station::SupportStation;
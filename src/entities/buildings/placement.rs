@@ -0,0 +1,350 @@
+use amethyst::{
+    core::{
+        nalgebra::{UnitQuaternion, Vector3},
+        transform::{ParentHierarchy, Transform},
+    },
+    ecs::{prelude::Entity, storage::GenericReadStorage, World},
+    shrev::EventChannel,
+};
+
+use entities::{
+    buildings::{Base, ConstructionSite, SupportStation},
+    Tile,
+};
+use level::{LevelGrid, TileChanged};
+use systems::OreStockpile;
+
+use std::{f32::consts::PI, fmt};
+
+/// The ore it costs to place a `Base`
+const BASE_COST: u32 = 10;
+
+/// The ore it costs to place a `SupportStation`
+const SUPPORT_STATION_COST: u32 = 5;
+
+/// The seconds of work it takes to build a `Base`, once its ore is delivered
+const BASE_BUILD_TIME: f32 = 10.;
+
+/// The seconds of work it takes to build a `SupportStation`, once its ore is delivered
+const SUPPORT_STATION_BUILD_TIME: f32 = 6.;
+
+/// The kinds of buildings, that the player can place
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub enum BuildingType {
+    Base,
+    SupportStation,
+}
+
+impl BuildingType {
+    /// The ore that has to be delivered from the `OreStockpile` to the `ConstructionSite` of the building
+    pub fn cost(self) -> u32 {
+        match self {
+            BuildingType::Base => BASE_COST,
+            BuildingType::SupportStation => SUPPORT_STATION_COST,
+        }
+    }
+
+    /// The seconds of work it takes a single `RockRaider` to build the building, once its ore is delivered
+    pub fn build_time(self) -> f32 {
+        match self {
+            BuildingType::Base => BASE_BUILD_TIME,
+            BuildingType::SupportStation => SUPPORT_STATION_BUILD_TIME,
+        }
+    }
+
+    /// The name of the building's mesh
+    pub fn asset_name(self) -> &'static str {
+        match self {
+            BuildingType::Base => Base::asset_name(),
+            BuildingType::SupportStation => SupportStation::asset_name(),
+        }
+    }
+
+    /// The width and depth of the building in `Tile`s, when it faces `Facing::North`
+    pub fn footprint(self) -> (i32, i32) {
+        match self {
+            BuildingType::Base => (1, 1),
+            BuildingType::SupportStation => (1, 2),
+        }
+    }
+
+    /// Returns the grid positions the building covers, when its front `Tile` is at (x, y).
+    /// The footprint reaches backwards and to the right from there, seen from the building. The front `Tile` always comes first.
+    pub fn covered_tiles(self, x: i32, y: i32, facing: Facing) -> Vec<(i32, i32)> {
+        let (width, depth) = self.footprint();
+        let (forward_x, forward_y) = facing.direction();
+        let (right_x, right_y) = (-forward_y, forward_x);
+
+        let mut covered = Vec::with_capacity((width * depth) as usize);
+        for back in 0..depth {
+            for right in 0..width {
+                covered.push((
+                    x + right * right_x - back * forward_x,
+                    y + right * right_y - back * forward_y,
+                ));
+            }
+        }
+        covered
+    }
+
+    /// The offset from the front `Tile` to the middle of the footprint, where the mesh is placed
+    pub fn center_offset(self, facing: Facing) -> Vector3<f32> {
+        let (width, depth) = self.footprint();
+        let (forward_x, forward_y) = facing.direction();
+        let right = (width - 1) as f32 / 2.;
+        let back = (depth - 1) as f32 / 2.;
+        Vector3::new(
+            -forward_y as f32 * right - forward_x as f32 * back,
+            0.,
+            forward_x as f32 * right - forward_y as f32 * back,
+        )
+    }
+
+    /// Builds the finished building with its front onto the `Tile` at (x, y), without checking whether it may be placed there. See `place_building`
+    pub fn build(self, x: i32, y: i32, facing: Facing, world: &World) -> Entity {
+        let tile = world.read_resource::<LevelGrid>().get(x, y).unwrap();
+        let building = match self {
+            BuildingType::Base => Base::build(&tile, world),
+            BuildingType::SupportStation => SupportStation::build(&tile, world),
+        };
+        self.occupy(building, x, y, facing, world);
+        building
+    }
+
+    /// Moves the entity, that is a child of the front `Tile` at (x, y), onto the footprint and turns it to the `Facing`.
+    ///
+    /// The covered `Tile`s cannot be walked on from now on, except for leaving the building through its entrance.
+    pub fn occupy(self, entity: Entity, x: i32, y: i32, facing: Facing, world: &World) {
+        {
+            // the entity is a child of its front `Tile`, so this is relative to it
+            let mut transforms = world.write_storage::<Transform>();
+            let transform = transforms.get_mut(entity).unwrap();
+            transform.set_position(self.center_offset(facing));
+            *transform.rotation_mut() = facing.rotation();
+        }
+
+        let covered = self.covered_tiles(x, y, facing);
+        let mut level_grid = world.write_resource::<LevelGrid>();
+        level_grid.cover(&covered, facing.in_front_of(x, y));
+
+        let mut tile_changes = world.write_resource::<EventChannel<TileChanged>>();
+        for &(x, y) in &covered {
+            tile_changes.single_write(TileChanged {
+                tile: level_grid.get(x, y).unwrap(),
+            });
+        }
+    }
+}
+
+/// The direction the entrance of a building faces. The entrance is the `Tile` in front of the building.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub enum Facing {
+    North,
+    East,
+    South,
+    West,
+}
+
+impl Facing {
+    /// Turns clockwise by a quarter
+    pub fn next(self) -> Self {
+        match self {
+            Facing::North => Facing::East,
+            Facing::East => Facing::South,
+            Facing::South => Facing::West,
+            Facing::West => Facing::North,
+        }
+    }
+
+    /// The step on the grid, that leads out of the entrance
+    pub fn direction(self) -> (i32, i32) {
+        match self {
+            Facing::North => (0, -1),
+            Facing::East => (1, 0),
+            Facing::South => (0, 1),
+            Facing::West => (-1, 0),
+        }
+    }
+
+    /// The grid position of the entrance of a building, whose front `Tile` is at (x, y)
+    pub fn in_front_of(self, x: i32, y: i32) -> (i32, i32) {
+        let (d_x, d_y) = self.direction();
+        (x + d_x, y + d_y)
+    }
+
+    /// The rotation of the building's mesh. The meshes look along their negative z axis, which is `Facing::North`
+    pub fn rotation(self) -> UnitQuaternion<f32> {
+        let angle = match self {
+            Facing::North => 0.,
+            Facing::East => -PI / 2.,
+            Facing::South => PI,
+            Facing::West => PI / 2.,
+        };
+        UnitQuaternion::from_axis_angle(&Vector3::<f32>::y_axis(), angle)
+    }
+}
+
+impl Default for Facing {
+    fn default() -> Self {
+        Facing::South
+    }
+}
+
+/// The reasons, why a building cannot be placed
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum PlacementError {
+    /// Another building stands on the `Tile` already or needs it as its entrance
+    Occupied,
+    /// The `Tile` in front of the building cannot be walked on, so the building could not be entered
+    BlockedEntrance,
+    /// Buildings can only be placed on `Tile::Ground`
+    NotGround(Tile),
+    /// The `Tile` was not revealed yet
+    Concealed,
+    /// The position is outside of the `LevelGrid`
+    OutOfBounds,
+    /// The `OreStockpile` holds less ore than the building costs
+    NotEnoughResources { needed: u32, available: u32 },
+}
+
+impl fmt::Display for PlacementError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            PlacementError::Occupied => write!(f, "another building stands there"),
+            PlacementError::BlockedEntrance => write!(f, "the entrance would be blocked"),
+            PlacementError::NotGround(tile) => write!(f, "cannot build on {:?}", tile),
+            PlacementError::Concealed => write!(f, "the tile is not revealed yet"),
+            PlacementError::OutOfBounds => write!(f, "the position is outside of the cave"),
+            PlacementError::NotEnoughResources { needed, available } => write!(
+                f,
+                "it costs {} ore, but only {} are in the stockpile",
+                needed, available
+            ),
+        }
+    }
+}
+
+/// Checks whether any building could stand on the `Tile` at (x, y) and returns the `Tile`'s entity if so.
+pub fn check_site<T: GenericReadStorage<Component = Tile>>(
+    x: i32,
+    y: i32,
+    level_grid: &LevelGrid,
+    tiles: &T,
+    hierarchy: &ParentHierarchy,
+) -> Result<Entity, PlacementError> {
+    let tile = level_grid.get(x, y).ok_or(PlacementError::OutOfBounds)?;
+
+    match tiles.get(tile).unwrap() {
+        Tile::Ground { concealed: false } => (),
+        Tile::Ground { concealed: true } => return Err(PlacementError::Concealed),
+        other => return Err(PlacementError::NotGround(*other)),
+    }
+
+    // if the tile has children, they have to be buildings so far.
+    // If we will ever have other children than buildings, we will have to test for every children, if it is a building
+    if level_grid.is_occupied(x, y) || !hierarchy.children(tile).is_empty() {
+        return Err(PlacementError::Occupied);
+    }
+    Ok(tile)
+}
+
+/// Checks whether the building could stand with its front on the `Tile` at (x, y).
+/// Every covered `Tile` has to be a site for a building and the entrance has to be walkable. Returns the front `Tile`'s entity if so.
+pub fn check_footprint<T: GenericReadStorage<Component = Tile>>(
+    building: BuildingType,
+    x: i32,
+    y: i32,
+    facing: Facing,
+    level_grid: &LevelGrid,
+    tiles: &T,
+    hierarchy: &ParentHierarchy,
+) -> Result<Entity, PlacementError> {
+    let covered = building.covered_tiles(x, y, facing);
+    for &(x, y) in &covered {
+        check_site(x, y, level_grid, tiles, hierarchy)?;
+    }
+
+    let (entrance_x, entrance_y) = facing.in_front_of(x, y);
+    if level_grid.is_occupied(entrance_x, entrance_y)
+        || !level_grid.is_walkable(entrance_x, entrance_y, tiles)
+    {
+        return Err(PlacementError::BlockedEntrance);
+    }
+    Ok(level_grid.get(x, y).unwrap())
+}
+
+/// Checks whether the building could stand with its front on (x, y) when the level starts, before any ground is revealed. `tile_at` returns the `Tile` at a grid position or `None` outside of the grid.
+/// Every covered `Tile` has to be ground and the entrance has to be walkable, once the ground around the building is revealed.
+pub fn check_start_footprint<F: Fn(i32, i32) -> Option<Tile>>(
+    building: BuildingType,
+    x: i32,
+    y: i32,
+    facing: Facing,
+    tile_at: F,
+) -> Result<(), PlacementError> {
+    for (x, y) in building.covered_tiles(x, y, facing) {
+        match tile_at(x, y) {
+            None => return Err(PlacementError::OutOfBounds),
+            Some(Tile::Ground { .. }) => (),
+            Some(other) => return Err(PlacementError::NotGround(other)),
+        }
+    }
+
+    let (entrance_x, entrance_y) = facing.in_front_of(x, y);
+    let entrance_walkable = tile_at(entrance_x, entrance_y).map_or(false, |mut tile| {
+        tile.reveal();
+        tile.is_walkable()
+    });
+    if !entrance_walkable {
+        return Err(PlacementError::BlockedEntrance);
+    }
+    Ok(())
+}
+
+/// Checks whether the building can be placed with its front onto the `Tile` at (x, y), including whether the player can afford it. Returns the `Tile`'s entity if so.
+pub fn check_placement<T: GenericReadStorage<Component = Tile>>(
+    building: BuildingType,
+    x: i32,
+    y: i32,
+    facing: Facing,
+    level_grid: &LevelGrid,
+    tiles: &T,
+    hierarchy: &ParentHierarchy,
+    stockpile: &OreStockpile,
+) -> Result<Entity, PlacementError> {
+    let tile = check_footprint(building, x, y, facing, level_grid, tiles, hierarchy)?;
+
+    if stockpile.ore < building.cost() {
+        return Err(PlacementError::NotEnoughResources {
+            needed: building.cost(),
+            available: stockpile.ore,
+        });
+    }
+    Ok(tile)
+}
+
+/// Places a `ConstructionSite` for the building with its front onto the `Tile` at (x, y).
+/// The building's ore is taken from the `OreStockpile` right away, so other sites cannot spend it as well.
+/// The `RockRaider`s carry it to the site and build it. See `ConstructionSystem`
+/// Returns the site's entity or the reason why the building cannot be placed there.
+pub fn place_building(
+    building: BuildingType,
+    x: i32,
+    y: i32,
+    facing: Facing,
+    world: &World,
+) -> Result<Entity, PlacementError> {
+    check_placement(
+        building,
+        x,
+        y,
+        facing,
+        &world.read_resource::<LevelGrid>(),
+        &world.read_storage::<Tile>(),
+        &world.read_resource::<ParentHierarchy>(),
+        &world.read_resource::<OreStockpile>(),
+    )?;
+
+    world.write_resource::<OreStockpile>().ore -= building.cost();
+    Ok(ConstructionSite::build(building, x, y, facing, world))
+}
@@ -0,0 +1,84 @@
+use amethyst::{
+    assets::{AssetStorage, Loader},
+    core::{
+        nalgebra::Vector3,
+        transform::{GlobalTransform, Parent, Transform},
+    },
+    ecs::prelude::{Component, Entity, NullStorage, World},
+    renderer::{PngFormat, Texture, TextureMetadata},
+};
+
+use assetmanagement::{util::insert_into_asset_storages, AssetManager};
+use eventhandling::{HoverHandlerComponent, SimpleHoverHandler};
+
+use ncollide3d::shape::Cuboid;
+
+/// A tag to indicate the entity as `SupportStation`
+/// A `SupportStation` produces oxygen as long as it stands. See `OxygenSystem`
+#[derive(Default)]
+pub struct SupportStation;
+
+impl SupportStation {
+    /// Creates a new `SupportStation` on the `Tile`, which is used as its `Parent` to determine the position.
+    /// This neither checks, whether the `SupportStation` may stand there, nor blocks the `Tile`s it covers. Use `BuildingType::build` or `place_building` for that.
+    pub fn build(tile: &Entity, world: &World) -> Entity {
+        let result = world
+            .entities()
+            .build_entity()
+            .with(
+                Transform::default(),
+                &mut world.write_storage::<Transform>(),
+            )
+            .with(
+                GlobalTransform::default(),
+                &mut world.write_storage::<GlobalTransform>(),
+            )
+            .with(SupportStation, &mut world.write_storage::<SupportStation>())
+            .with(
+                Parent { entity: *tile },
+                &mut world.write_storage::<Parent>(),
+            )
+            .build();
+
+        {
+            let loader = world.read_resource();
+            let mut tex_manager = world.write_resource();
+            let mut tex_storage = world.write_resource();
+            let handler =
+                SupportStation::new_hover_handler(&loader, &mut tex_manager, &mut tex_storage);
+            world.write_storage().insert(result, handler).unwrap();
+        }
+
+        {
+            let mut storages = world.system_data();
+            insert_into_asset_storages(result, SupportStation::asset_name(), &mut storages);
+        }
+
+        result
+    }
+
+    pub fn asset_name() -> &'static str {
+        "buildings/support_station"
+    }
+
+    pub fn new_hover_handler(
+        loader: &Loader,
+        tex_manager: &mut AssetManager<Texture>,
+        mut tex_storage: &mut AssetStorage<Texture>,
+    ) -> HoverHandlerComponent {
+        let hover_mat = tex_manager.get_asset_handle_or_load(
+            "buildings/support_station_hover",
+            PngFormat,
+            TextureMetadata::srgb(),
+            &mut tex_storage,
+            &loader,
+        );
+
+        let bounding_box = Cuboid::new(Vector3::new(0.3, 0.3, 0.3));
+        Box::new(SimpleHoverHandler::new(bounding_box, hover_mat))
+    }
+}
+
+impl Component for SupportStation {
+    type Storage = NullStorage<SupportStation>;
+}
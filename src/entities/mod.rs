@@ -1,7 +1,10 @@
 pub mod buildings;
+mod ore;
 mod rock_raider;
 mod tile;
 
+pub use self::ore::Ore;
+pub use self::ore::OreStorages;
 pub use self::rock_raider::RockRaider;
-pub use self::rock_raider::RockRaiderStorages;
-pub use self::tile::Tile;
+pub use self::rock_raider::{RockRaiderRemovalStorages, RockRaiderStorages};
+pub use self::tile::{Tile, WallMaterial};
@@ -0,0 +1,168 @@
+use amethyst::{
+    assets::{AssetStorage, Loader},
+    core::{
+        nalgebra::{Point2, Vector3},
+        transform::{GlobalTransform, Transform},
+    },
+    ecs::prelude::{Component, Entities, Entity, NullStorage, World, WriteStorage},
+    renderer::{PngFormat, Texture, TextureMetadata},
+};
+use assetmanagement::{util::*, AssetManager};
+use entities::Tile;
+use eventhandling::{ClickHandlerComponent, Clickable, HoverHandlerComponent, SimpleHoverHandler};
+use level::{LevelGrid, SelectedRockRaiders};
+use ncollide3d::shape::Cuboid;
+use systems::{
+    AssignedJob, Clear, CollectOre, Construct, Drill, FlowDestination, JobBoard, JobKind, Path,
+    TeleportOut,
+};
+
+/// A Tag to indicate the entity as `Ore`, that lies on the ground and can be picked up.
+/// `Ore` drops out of drilled walls and is brought to the `Base` by `RockRaider`s. See `OreCollectionSystem`
+#[derive(Default)]
+pub struct Ore;
+
+pub type OreStorages<'a> = (
+    WriteStorage<'a, Ore>,
+    WriteStorage<'a, Transform>,
+    WriteStorage<'a, GlobalTransform>,
+    AssetStorages<'a>,
+    WriteStorage<'a, HoverHandlerComponent>,
+    WriteStorage<'a, ClickHandlerComponent>,
+);
+
+impl Ore {
+    pub fn instantiate(
+        entities: &Entities,
+        position: Point2<f32>,
+        ore_storages: &mut OreStorages,
+    ) -> Entity {
+        let (
+            ref mut ore_storage,
+            ref mut transform_storage,
+            ref mut global_transform_storage,
+            ref mut asset_storages,
+            ref mut hover_storage,
+            ref mut click_storage,
+        ) = ore_storages;
+
+        let mut transform = Transform::default();
+        transform.set_position(Vector3::new(position.x, 0.0, position.y));
+
+        let entity = entities
+            .build_entity()
+            .with(Ore, ore_storage)
+            .with(transform, transform_storage)
+            .with(GlobalTransform::default(), global_transform_storage)
+            .build();
+
+        insert_into_asset_storages(entity, Ore::asset_name(), asset_storages);
+
+        let (
+            ref loader,
+            ref _mesh_manager,
+            ref _mesh_handles,
+            ref _mesh_storage,
+            ref mut tex_manager,
+            ref _mat_storage,
+            ref mut tex_storage,
+            ref _default_mat,
+        ) = asset_storages;
+
+        let handler = Self::new_hover_handler(&loader, tex_manager, tex_storage);
+        hover_storage.insert(entity, handler).unwrap();
+
+        click_storage
+            .insert(entity, Ore::new_click_handler())
+            .unwrap();
+
+        entity
+    }
+
+    fn asset_name() -> &'static str {
+        "ore"
+    }
+
+    pub fn new_hover_handler(
+        loader: &Loader,
+        tex_manager: &mut AssetManager<Texture>,
+        mut tex_storage: &mut AssetStorage<Texture>,
+    ) -> HoverHandlerComponent {
+        let hover_mat = tex_manager.get_asset_handle_or_load(
+            "ore_hover",
+            PngFormat,
+            TextureMetadata::srgb(),
+            &mut tex_storage,
+            &loader,
+        );
+
+        let bounding_box = Cuboid::new(Vector3::new(0.08, 0.06, 0.08));
+        Box::new(SimpleHoverHandler::new(bounding_box, hover_mat))
+    }
+
+    pub fn new_click_handler() -> ClickHandlerComponent {
+        Box::new(Ore) as ClickHandlerComponent
+    }
+}
+
+impl Component for Ore {
+    type Storage = NullStorage<Ore>;
+}
+
+impl Clickable for Ore {
+    fn on_click(&self, entity: Entity, world: &World) {
+        let selected: Vec<Entity> = world
+            .write_resource::<SelectedRockRaiders>()
+            .drain(..)
+            .collect();
+
+        // carried ore lost its `Ore` tag and cannot be picked up
+        if world.read_storage::<Ore>().get(entity).is_none() {
+            return;
+        }
+
+        // without a selected `RockRaider`, the ore is collected by the next idle one
+        if selected.is_empty() {
+            world
+                .write_resource::<JobBoard>()
+                .post(JobKind::CollectOre, entity);
+            return;
+        }
+
+        let level_grid = world.read_resource::<LevelGrid>();
+        let tiles = world.read_storage::<Tile>();
+        let transforms = world.read_storage::<Transform>();
+        let (x, y) = LevelGrid::grid_position(transforms.get(entity).unwrap().translation());
+        let destination = level_grid.get(x, y).unwrap();
+
+        // only one `RockRaider` can carry the ore, so the first selected one, that can reach it, fetches it
+        for rock_raider in selected {
+            let start = match transforms.get(rock_raider) {
+                Some(transform) => {
+                    let (x, y) = LevelGrid::grid_position(transform.translation());
+                    level_grid.get(x, y).unwrap()
+                }
+                // the `RockRaider` was deleted since it was selected
+                None => continue,
+            };
+
+            if let Some(path) = level_grid.find_path(start, destination, &tiles, &transforms) {
+                world.write_storage::<AssignedJob>().remove(rock_raider);
+                world.write_storage::<TeleportOut>().remove(rock_raider);
+                world.write_storage::<Drill>().remove(rock_raider);
+                world.write_storage::<Clear>().remove(rock_raider);
+                world.write_storage::<FlowDestination>().remove(rock_raider);
+                Construct::cancel(rock_raider, world);
+                world
+                    .write_storage::<Path>()
+                    .insert(rock_raider, path)
+                    .unwrap();
+                world
+                    .write_storage()
+                    .insert(rock_raider, CollectOre::Fetch(entity))
+                    .unwrap();
+                return;
+            }
+        }
+    }
+}
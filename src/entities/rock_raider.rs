@@ -2,30 +2,54 @@ use amethyst::{
     assets::{AssetStorage, Loader},
     core::{
         nalgebra::{Point2, Vector3},
-        transform::{GlobalTransform, Transform},
+        transform::{GlobalTransform, Parent, Transform},
     },
-    ecs::prelude::{Component, Entities, Entity, NullStorage, World, WriteStorage},
+    ecs::prelude::{Component, Entities, Entity, NullStorage, World, Write, WriteStorage},
     renderer::{PngFormat, Texture, TextureMetadata},
 };
 use assetmanagement::{util::*, AssetManager};
-use eventhandling::{ClickHandlerComponent, Clickable, HoverHandlerComponent, SimpleHoverHandler};
-use level::SelectedRockRaider;
+use entities::Ore;
+use eventhandling::{
+    ClickHandlerComponent, Clickable, HoverHandlerComponent, Hovered, SimpleHoverHandler,
+};
+use level::SelectedRockRaiders;
 use ncollide3d::shape::Cuboid;
+use systems::{CarrySlot, Health, MoveSpeed};
 
 /// A Tag to indicate the entity as `RockRader`
 /// `RockRaider`a are the little moving people, that the player can control to do certain tasks ;).
+/// What they carry is held in their `CarrySlot`.
 #[derive(Default)]
 pub struct RockRaider;
 
+/// The `Health` every `RockRaider` starts with
+const MAX_HEALTH: f32 = 100.;
+
 pub type RockRaiderStorages<'a> = (
     (
         WriteStorage<'a, RockRaider>,
         WriteStorage<'a, Transform>,
         WriteStorage<'a, GlobalTransform>,
+        WriteStorage<'a, Health>,
+        WriteStorage<'a, MoveSpeed>,
+        WriteStorage<'a, CarrySlot>,
     ),
     AssetStorages<'a>,
 );
 
+/// The storages needed to remove a `RockRaider` from the level. See `RockRaider::remove`
+pub type RockRaiderRemovalStorages<'a> = (
+    Entities<'a>,
+    Write<'a, Hovered>,
+    Write<'a, SelectedRockRaiders>,
+    WriteStorage<'a, HoverHandlerComponent>,
+    WriteStorage<'a, ClickHandlerComponent>,
+    WriteStorage<'a, CarrySlot>,
+    WriteStorage<'a, Parent>,
+    WriteStorage<'a, Transform>,
+    WriteStorage<'a, Ore>,
+);
+
 impl RockRaider {
     pub fn instantiate(
         entities: &Entities, //note: this is a type alias for Read<'a, EntityRes>
@@ -35,7 +59,14 @@ impl RockRaider {
         mut click_storage: WriteStorage<ClickHandlerComponent>,
     ) -> Entity {
         let (
-            (mut rock_raider_storage, mut transform_storage, mut global_transform_storage),
+            (
+                mut rock_raider_storage,
+                mut transform_storage,
+                mut global_transform_storage,
+                mut health_storage,
+                mut move_speed_storage,
+                mut carry_slot_storage,
+            ),
             mut asset_storages,
         ) = rr_storages;
 
@@ -47,6 +78,9 @@ impl RockRaider {
             .with(RockRaider, &mut rock_raider_storage)
             .with(transform, &mut transform_storage)
             .with(GlobalTransform::default(), &mut global_transform_storage)
+            .with(Health::new(MAX_HEALTH), &mut health_storage)
+            .with(MoveSpeed::default(), &mut move_speed_storage)
+            .with(CarrySlot::default(), &mut carry_slot_storage)
             .build();
 
         insert_into_asset_storages(entity, RockRaider::asset_name(), &mut asset_storages);
@@ -72,6 +106,38 @@ impl RockRaider {
         entity
     }
 
+    /// Removes the `RockRaider` from the level together with its hover and click handler, like when it died or teleported out.
+    /// The item it carries is put onto the ground, so it is not lost.
+    pub fn remove(entity: Entity, storages: &mut RockRaiderRemovalStorages) {
+        let (
+            entities,
+            hovered,
+            selected,
+            hover_storage,
+            click_storage,
+            carry_slots,
+            parents,
+            transforms,
+            ores,
+        ) = storages;
+
+        if let Some(carry_slot) = carry_slots.get_mut(entity) {
+            let position = *transforms.get(entity).unwrap().translation();
+            if let Some(item) = carry_slot.drop_off(&position, parents, transforms) {
+                ores.insert(item, Ore).unwrap();
+            }
+        }
+
+        hover_storage.remove(entity);
+        click_storage.remove(entity);
+        if hovered.0 == Some(entity) {
+            hovered.0 = None;
+        }
+        selected.retain(|&rock_raider| rock_raider != entity);
+
+        entities.delete(entity).unwrap();
+    }
+
     fn asset_name() -> &'static str {
         "/rock_raider/default"
     }
@@ -104,6 +170,6 @@ impl Component for RockRaider {
 
 impl Clickable for RockRaider {
     fn on_click(&self, entity: Entity, world: &World) {
-        *world.write_resource::<Option<SelectedRockRaider>>() = Some(SelectedRockRaider(entity));
+        **world.write_resource::<SelectedRockRaiders>() = vec![entity];
     }
 }
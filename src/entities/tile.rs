@@ -6,20 +6,34 @@ use amethyst::{
 };
 
 use assetmanagement::AssetManager;
+use entities::buildings::place_building;
 use eventhandling::{ClickHandlerComponent, Clickable, HoverHandlerComponent, SimpleHoverHandler};
-use level::{LevelGrid, SelectedRockRaider};
+use level::{LevelGrid, SelectedRockRaiders};
 use ncollide3d::shape::Cuboid;
+use systems::{
+    AssignedJob, Clear, CollectOre, Construct, Drill, FlowDestination, JobBoard, JobKind, Path,
+    PlacementMode, TeleportOut,
+};
 
 /// A Component which indicates the entity as a `Tile`, meaning it represents one part of the grid that stores the information of the cave's geography
 #[derive(Clone, Copy, Debug, Serialize, Deserialize, PartialEq)]
 pub enum Tile {
     Wall {
-        breaks: bool,
+        material: WallMaterial,
         ore: u8,
     },
     Ground {
         concealed: bool,
     },
+    /// Floor, that cannot be walked on
+    Water,
+    /// Floor, that cannot be walked on and hurts every `RockRaider` standing next to it
+    Lava,
+    /// The remains of a collapsed wall. It can be walked on, but slowly.
+    /// Every clearing pass lowers the `level` by one, until it is plain ground.
+    Rubble {
+        level: u8,
+    },
 
     /// Convenience Tile, Should never see be seen in actual grids... only exist for comparison
     Any,
@@ -45,19 +59,75 @@ impl Tile {
             (Tile::Ground { concealed: false }, Tile::Ground { concealed: false }) => true, // Ground is Ground, when it was revealed
             (Tile::Ground { concealed: true }, Tile::Wall { .. }) => true, //concealed Ground is hidden as wall mesh
             (Tile::Wall { .. }, Tile::Ground { concealed: true }) => true,
+            (Tile::Water, Tile::Water) => true,
+            (Tile::Lava, Tile::Lava) => true,
+            (Tile::Rubble { .. }, Tile::Rubble { .. }) => true,
             (Tile::Any, _) => true,
             (_, Tile::Any) => true,
             _ => false,
         }
     }
 
+    /// Returns one `Tile` of every class, that `pattern_eq` can tell apart.
+    ///
+    /// Concealed ground is left out, because it matches exactly like a wall. `Tile::Any` is only used in patterns.
+    pub fn pattern_classes() -> Vec<Tile> {
+        vec![
+            Tile::default(),
+            Tile::Ground { concealed: false },
+            Tile::Water,
+            Tile::Lava,
+            Tile::Rubble { level: 1 },
+        ]
+    }
+
+    /// Returns how this `Tile` is seen by the patterns of the `Tile`s next to it.
+    ///
+    /// Water, lava and rubble are floor, so a wall next to them gets the same mesh as a wall next to revealed ground.
+    pub fn as_neighbor(&self) -> Tile {
+        match self {
+            Tile::Water | Tile::Lava | Tile::Rubble { .. } => Tile::Ground { concealed: false },
+            _ => *self,
+        }
+    }
+
+    /// Returns the seconds it takes to drill this `Tile` or `None` if the `Tile` cannot be drilled at all.
+    pub fn drill_time(&self) -> Option<f32> {
+        match self {
+            Tile::Wall { material, .. } => material.drill_time(),
+            _ => None,
+        }
+    }
+
     pub fn is_walkable(&self) -> bool {
         match self {
-            Tile::Ground { concealed: false } => true,
+            Tile::Ground { concealed: false } | Tile::Rubble { .. } => true,
             _ => false,
         }
     }
 
+    /// Returns how many times longer it takes to cross this `Tile` than to cross plain ground or `None` if the `Tile` is not walkable.
+    ///
+    /// Pathfinding weighs every step with this cost. It must never be below 1, otherwise `find_grid_path` may miss the cheapest path.
+    pub fn movement_cost(&self) -> Option<f32> {
+        match self {
+            Tile::Ground { concealed: false } => Some(1.),
+            Tile::Rubble { level } => Some(1. + 0.5 * f32::from(*level)),
+            _ => None,
+        }
+    }
+
+    /// Removes one level of rubble. The last level leaves revealed ground behind. Does nothing to any other `Tile`.
+    pub fn clear(&mut self) {
+        if let Tile::Rubble { level } = *self {
+            *self = if level > 1 {
+                Tile::Rubble { level: level - 1 }
+            } else {
+                Tile::Ground { concealed: false }
+            };
+        }
+    }
+
     pub fn new_hover_handler(
         loader: &Loader,
         tex_manager: &mut AssetManager<Texture>,
@@ -78,6 +148,7 @@ impl Tile {
     pub fn new_click_handler() -> ClickHandlerComponent {
         // TODO Refactor
         // This is working, because there are currently no different clickhandler for different Tiles.
+        // The handler looks up the clicked `Tile` itself to decide whether to walk there or to drill it.
         // A rr does not move to a Wall, because there will be no Path to the Wall(the destination is not `walkable()`)
         // is updated as soon as different ClickHandler are required
         Box::new(Tile::Any) as Box<dyn Clickable>
@@ -87,37 +158,187 @@ impl Tile {
 impl Default for Tile {
     fn default() -> Self {
         Tile::Wall {
-            breaks: false,
+            material: WallMaterial::default(),
             ore: 0,
         }
     }
 }
 
+/// The rock a `Tile::Wall` is made of. It determines, whether and how fast the wall can be drilled.
+///
+/// Note, that `Tile::pattern_eq` ignores the material, so walls of every material share the same meshes and only differ in their texture.
+#[derive(Clone, Copy, Debug, Serialize, Deserialize, PartialEq)]
+pub enum WallMaterial {
+    Dirt,
+    LooseRock,
+    HardRock,
+    /// Cannot be drilled at all
+    SolidRock,
+}
+
+impl WallMaterial {
+    /// Every material there is.
+    pub fn all() -> [WallMaterial; 4] {
+        [
+            WallMaterial::Dirt,
+            WallMaterial::LooseRock,
+            WallMaterial::HardRock,
+            WallMaterial::SolidRock,
+        ]
+    }
+
+    /// Returns the seconds it takes to drill a wall of this material or `None` if it cannot be drilled.
+    pub fn drill_time(&self) -> Option<f32> {
+        match self {
+            WallMaterial::Dirt => Some(2.),
+            WallMaterial::LooseRock => Some(4.),
+            WallMaterial::HardRock => Some(8.),
+            WallMaterial::SolidRock => None,
+        }
+    }
+
+    /// The name of the texture, that all wall meshes of this material use.
+    pub fn texture_name(&self) -> &'static str {
+        match self {
+            WallMaterial::Dirt => "walls/dirt",
+            WallMaterial::LooseRock => "walls/loose_rock",
+            WallMaterial::HardRock => "walls/hard_rock",
+            WallMaterial::SolidRock => "walls/solid_rock",
+        }
+    }
+}
+
+impl Default for WallMaterial {
+    fn default() -> Self {
+        WallMaterial::SolidRock
+    }
+}
+
 impl Component for Tile {
     type Storage = DenseVecStorage<Tile>;
 }
 
 impl Clickable for Tile {
     fn on_click(&self, entity: Entity, world: &World) {
-        if let Some(SelectedRockRaider(rock_raider)) =
-            *world.write_resource::<Option<SelectedRockRaider>>()
-        {
-            // Destination is the clicked entity
-            let level_grid = world.read_resource::<LevelGrid>();
-            let tiles = world.read_storage::<Tile>();
-            let transforms = world.read_storage::<Transform>();
-
-            let transform = transforms.get(rock_raider).unwrap().translation();
-            let x = (transform.x + 0.5) as i32;
-            let y = (transform.z + 0.5) as i32;
-
-            let start = level_grid.get(x, y).unwrap();
-            let path = level_grid.find_path(start, entity, &tiles, &transforms);
-
-            if let Some(path) = path {
-                world.write_storage().insert(rock_raider, path).unwrap();
+        // while the player places a building, a click places it on the `Tile` instead of giving orders
+        let placing = world
+            .read_resource::<Option<PlacementMode>>()
+            .as_ref()
+            .map(|mode| (mode.building, mode.facing));
+        if let Some((building, facing)) = placing {
+            let (x, y) = LevelGrid::grid_position(
+                world
+                    .read_storage::<Transform>()
+                    .get(entity)
+                    .unwrap()
+                    .translation(),
+            );
+            match place_building(building, x, y, facing, world) {
+                Ok(_) => {
+                    let placed = world.write_resource::<Option<PlacementMode>>().take();
+                    placed.unwrap().stop(world);
+                }
+                Err(error) => warn!("Cannot place {:?} at ({}, {}): {}", building, x, y, error),
             }
-        };
-        *world.write_resource::<Option<SelectedRockRaider>>() = None;
+            return;
+        }
+
+        let selected: Vec<Entity> = world
+            .write_resource::<SelectedRockRaiders>()
+            .drain(..)
+            .collect();
+
+        let level_grid = world.read_resource::<LevelGrid>();
+        let tiles = world.read_storage::<Tile>();
+
+        // without a selected `RockRaider`, the work is posted for the next idle one
+        if selected.is_empty() {
+            let kind = match tiles.get(entity).unwrap() {
+                tile if tile.drill_time().is_some() => Some(JobKind::Drill),
+                Tile::Rubble { .. } => Some(JobKind::ClearRubble),
+                _ => None,
+            };
+            if let Some(kind) = kind {
+                world.write_resource::<JobBoard>().post(kind, entity);
+            }
+            return;
+        }
+
+        // carried `Ore` is put down, carried reserved ore goes back to its `ConstructionSite`
+        for &rock_raider in &selected {
+            CollectOre::cancel(rock_raider, world);
+            Construct::cancel(rock_raider, world);
+        }
+
+        let transforms = world.read_storage::<Transform>();
+        let mut paths = world.write_storage::<Path>();
+        let mut drills = world.write_storage::<Drill>();
+        let mut clears = world.write_storage::<Clear>();
+        let mut flow_destinations = world.write_storage::<FlowDestination>();
+        let mut assigned_jobs = world.write_storage::<AssignedJob>();
+        let mut teleports = world.write_storage::<TeleportOut>();
+
+        // `RockRaider`s may have been deleted since they were selected
+        let rock_raiders: Vec<(Entity, Entity)> = selected
+            .into_iter()
+            .filter_map(|rock_raider| {
+                let (x, y) = LevelGrid::grid_position(transforms.get(rock_raider)?.translation());
+                level_grid.get(x, y).map(|start| (rock_raider, start))
+            })
+            .collect();
+
+        // every new order cancels the job, the teleport, the drilling, clearing, carrying and walking to a `FlowDestination`
+        for &(rock_raider, _) in &rock_raiders {
+            assigned_jobs.remove(rock_raider);
+            teleports.remove(rock_raider);
+            drills.remove(rock_raider);
+            clears.remove(rock_raider);
+            flow_destinations.remove(rock_raider);
+        }
+
+        let tile = tiles.get(entity).unwrap();
+        if let Some(drill_time) = tile.drill_time() {
+            // Destination is any walkable tile next to the clicked wall
+            for (rock_raider, start) in rock_raiders {
+                let path = level_grid.find_path_to_neighbor_of(start, entity, &tiles, &transforms);
+
+                if let Some(path) = path {
+                    paths.insert(rock_raider, path).unwrap();
+                    drills
+                        .insert(rock_raider, Drill::new(entity, drill_time))
+                        .unwrap();
+                }
+            }
+        } else {
+            // Destination is the clicked entity. Several `RockRaider`s are spread over the tiles around it, every one walks to the nearest tile that is left.
+            let (x, y) = LevelGrid::grid_position(transforms.get(entity).unwrap().translation());
+            let spread = level_grid.spread_around(x, y, rock_raiders.len(), &tiles);
+            let mut destinations = spread.clone();
+
+            for (rock_raider, start) in rock_raiders {
+                // the area is too small, so the tiles are shared
+                if destinations.is_empty() {
+                    destinations = spread.clone();
+                }
+                if destinations.is_empty() {
+                    break;
+                }
+
+                let path =
+                    level_grid.find_path_to_nearest(start, &destinations, &tiles, &transforms);
+
+                if let Some(path) = path {
+                    let destination = path.last().unwrap();
+                    let destination = (destination.x as i32, destination.y as i32);
+                    destinations.retain(|&position| position != destination);
+                    paths.insert(rock_raider, path).unwrap();
+
+                    // rubble is cleared, as soon as the `RockRaider`s arrived at it
+                    if let Tile::Rubble { .. } = tile {
+                        clears.insert(rock_raider, Clear::new(entity)).unwrap();
+                    }
+                }
+            }
+        }
     }
 }
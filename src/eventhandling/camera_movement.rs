@@ -9,6 +9,23 @@ use amethyst::{
     renderer::{ActiveCamera, ScreenDimensions},
 };
 
+/// Moves the camera over the ground, until it looks at the position. The height and angle of the camera stay the same.
+pub fn center_camera_on(camera: &mut Transform, position: &Vector3<f32>) {
+    let forward = camera.rotation() * -Vector3::z();
+    // the camera does not look down onto the ground
+    if forward.y >= 0. {
+        return;
+    }
+
+    let looked_at =
+        camera.translation() + forward * (position.y - camera.translation().y) / forward.y;
+    camera.move_global(Vector3::new(
+        position.x - looked_at.x,
+        0.,
+        position.z - looked_at.z,
+    ));
+}
+
 pub struct CameraMovementSystem;
 
 impl<'a> System<'a> for CameraMovementSystem {
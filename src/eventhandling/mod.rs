@@ -3,7 +3,7 @@ mod clicking;
 mod hovering;
 mod mouse_ray;
 
-pub use self::camera_movement::CameraMovementSystem;
+pub use self::camera_movement::{center_camera_on, CameraMovementSystem};
 pub use self::clicking::*;
 pub use self::hovering::*;
 pub use self::mouse_ray::*;
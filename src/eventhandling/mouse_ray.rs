@@ -65,6 +65,28 @@ fn from_window_space(
     Vector3::new(v.x, v.y, v.z)
 }
 
+/// Projects a position of the world onto the window. This is the reverse of `from_window_space`.
+/// Returns `None`, if the position is behind the camera.
+pub fn to_window_space(
+    position: &Vector3<f32>,
+    (width, height): (f32, f32),
+    proj: Matrix4<f32>,
+    view: Matrix4<f32>,
+) -> Option<(f32, f32)> {
+    let v = proj
+        * view
+            .try_inverse()
+            .expect("Could not build inverse matrix of the camera's transform")
+        * Vector4::new(position.x, position.y, position.z, 1.);
+    if v.w <= 0. {
+        return None;
+    }
+    Some((
+        (v.x / v.w + 1.) / 2. * width,
+        height - (v.y / v.w + 1.) / 2. * height,
+    ))
+}
+
 pub struct MouseRay {
     pub ray: Ray<f32>,
 }
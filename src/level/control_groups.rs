@@ -0,0 +1,59 @@
+use amethyst::ecs::{prelude::Entity, world::EntitiesRes, ReadStorage};
+
+use entities::RockRaider;
+
+/// The number of control groups, one for every digit key
+pub const CONTROL_GROUPS: usize = 10;
+
+/// Two recalls of the same group within this many seconds are a double tap
+const DOUBLE_TAP_SECONDS: f64 = 0.3;
+
+/// A `Resource`, that stores the numbered groups of `RockRaider`s, that the player saved to select them again later.
+#[derive(Default)]
+pub struct ControlGroups {
+    groups: [Vec<Entity>; CONTROL_GROUPS],
+    /// The group that was recalled last, together with the time of the recall in seconds
+    last_recall: Option<(usize, f64)>,
+}
+
+impl ControlGroups {
+    /// Saves the `RockRaider`s as the group with the given number. The group saved there before is replaced.
+    pub fn save(
+        &mut self,
+        group: usize,
+        selected: &[Entity],
+        entities: &EntitiesRes,
+        rock_raiders: &ReadStorage<RockRaider>,
+    ) {
+        self.groups[group] = selected.to_vec();
+        self.drop_gone(entities, rock_raiders);
+        self.last_recall = None;
+    }
+
+    /// Returns the `RockRaider`s of the group with the given number.
+    /// The second value is `true`, if the same group was recalled right before, so the player tapped its key twice.
+    pub fn recall(
+        &mut self,
+        group: usize,
+        now: f64,
+        entities: &EntitiesRes,
+        rock_raiders: &ReadStorage<RockRaider>,
+    ) -> (Vec<Entity>, bool) {
+        self.drop_gone(entities, rock_raiders);
+
+        let double_tap = self.last_recall.map_or(false, |(last_group, last_time)| {
+            last_group == group && now - last_time <= DOUBLE_TAP_SECONDS
+        });
+        // a third tap counts as the first one again
+        self.last_recall = if double_tap { None } else { Some((group, now)) };
+
+        (self.groups[group].clone(), double_tap)
+    }
+
+    /// Removes every entity from the groups, that is no `RockRaider` anymore, because it died or teleported out.
+    fn drop_gone(&mut self, entities: &EntitiesRes, rock_raiders: &ReadStorage<RockRaider>) {
+        for group in self.groups.iter_mut() {
+            group.retain(|&entity| entities.is_alive(entity) && rock_raiders.get(entity).is_some());
+        }
+    }
+}
@@ -0,0 +1,38 @@
+use amethyst::ecs::storage::GenericReadStorage;
+
+use entities::Tile;
+use level::{FlowField, LevelGrid};
+
+use std::collections::HashMap;
+
+/// A `Resource`, that caches a `FlowField` for every set of destinations, that entities are heading to.
+///
+/// The cache has to be invalidated whenever a `Tile` changes. This is done by the `RepathingSystem`.
+#[derive(Default)]
+pub struct FlowFields {
+    fields: HashMap<Vec<(i32, i32)>, FlowField>,
+}
+
+impl FlowFields {
+    /// Returns the `FlowField` towards the nearest of the destinations. It is computed, if it is not cached yet.
+    pub fn get<T: GenericReadStorage<Component = Tile>>(
+        &mut self,
+        destinations: &[(i32, i32)],
+        level_grid: &LevelGrid,
+        tiles: &T,
+    ) -> &FlowField {
+        self.fields.entry(destinations.to_vec()).or_insert_with(|| {
+            FlowField::new(
+                destinations,
+                level_grid.x_len(),
+                level_grid.y_len(),
+                |from, to| level_grid.movement_cost(from, to, tiles),
+            )
+        })
+    }
+
+    /// Drops every cached `FlowField`.
+    pub fn invalidate(&mut self) {
+        self.fields.clear();
+    }
+}
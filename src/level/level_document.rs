@@ -0,0 +1,212 @@
+use amethyst::config::{Config, ConfigError};
+
+use entities::Tile;
+
+use std::{io, path::Path};
+
+/// The version of the level format that is written by the current code.
+/// Increase this whenever `LevelDocument` changes in a way old files cannot be read anymore and add a migration to `LevelDocument::load_from`.
+///
+/// * version 1: first versioned format
+/// * version 2: walls have a `WallMaterial` instead of `breaks`
+pub const CURRENT_LEVEL_VERSION: u32 = 2;
+
+/// The on-disk representation of a level.
+///
+/// example:
+/// ```
+/// (
+///     version: 2,
+///     info: (
+///         name: "First Contact",
+///         description: "Find your way through the cave",
+///         oxygen: 100.,
+///         base_position: (2, 0),
+///         raiders: [(2, 1)],
+///         objectives: [CollectOre(amount: 10)],
+///         oxygen_rates: (per_raider: 1., per_support_station: 4.),
+///         raider_cost: 2,
+///     ),
+///     grid: [[Ground(concealed:true), ...], ...],
+/// )
+/// ```
+#[derive(Debug, Serialize, Deserialize)]
+pub struct LevelDocument {
+    /// The version of the format, the document was written in
+    pub version: u32,
+    /// Everything the level tells about itself, except for the cave's layout
+    pub info: LevelInfo,
+    /// The cave's layout. See `LevelGrid`
+    pub grid: Vec<Vec<Tile>>,
+}
+
+/// A `Resource`, that holds the metadata of the level that is currently played.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct LevelInfo {
+    pub name: String,
+    #[serde(default)]
+    pub description: String,
+    /// The amount of oxygen the cave is filled with, when the level starts
+    pub oxygen: f32,
+    /// The grid position of the `Base`
+    pub base_position: (i32, i32),
+    /// The grid positions of the `RockRaider`s the player starts with
+    #[serde(default)]
+    pub raiders: Vec<(i32, i32)>,
+    #[serde(default)]
+    pub objectives: Vec<Objective>,
+    /// How fast oxygen is breathed and produced
+    #[serde(default)]
+    pub oxygen_rates: OxygenRates,
+    /// The ore it costs to teleport in another `RockRaider`. They are free, if this is not set
+    #[serde(default)]
+    pub raider_cost: u32,
+}
+
+/// Something the player has to achieve to win the level.
+#[derive(Clone, Debug, Serialize, Deserialize, PartialEq)]
+pub enum Objective {
+    /// Deliver the given amount of ore to the `Base`
+    CollectOre { amount: u32 },
+    /// Survive in the cave for the given amount of seconds
+    Survive { seconds: u32 },
+}
+
+impl Default for LevelInfo {
+    /// These are the values that were hard-coded before levels could carry any metadata.
+    fn default() -> Self {
+        LevelInfo {
+            name: String::from("Unnamed Level"),
+            description: String::new(),
+            oxygen: 100.,
+            base_position: (2, 0),
+            raiders: vec![],
+            objectives: vec![],
+            oxygen_rates: OxygenRates::default(),
+            raider_cost: 0,
+        }
+    }
+}
+
+/// The oxygen per second, that is breathed and produced in a level. Level designers balance the level's oxygen with these.
+#[derive(Clone, Debug, Serialize, Deserialize, PartialEq)]
+#[serde(default)]
+pub struct OxygenRates {
+    /// The oxygen every `RockRaider` breathes per second
+    pub per_raider: f32,
+    /// The oxygen every `SupportStation` produces per second
+    pub per_support_station: f32,
+}
+
+impl Default for OxygenRates {
+    /// Every `RockRaider` breathing one unit per second is what was hard-coded before.
+    fn default() -> Self {
+        OxygenRates {
+            per_raider: 1.,
+            per_support_station: 4.,
+        }
+    }
+}
+
+impl LevelDocument {
+    /// Loads a level from disk.
+    ///
+    /// Files of older versions are migrated on the fly. This includes files that only contain a bare `Vec<Vec<Tile>>` (the format before levels had a version).
+    pub fn load_from(path: &Path) -> Result<LevelDocument, ConfigError> {
+        let error = match LevelDocument::load_no_fallback(path) {
+            Ok(document) => return document.check_version(),
+            Err(error) => error,
+        };
+
+        // the file may be of an older format
+        if let Ok(document) = v1::LevelDocument::load_no_fallback(path) {
+            warn!(
+                "{} is a level file of version 1. Consider converting it to version {}",
+                path.display(),
+                CURRENT_LEVEL_VERSION
+            );
+            return Ok(LevelDocument {
+                version: CURRENT_LEVEL_VERSION,
+                info: document.info,
+                grid: v1::migrate_grid(document.grid),
+            });
+        }
+        if let Ok(grid) = Vec::<Vec<v1::Tile>>::load_no_fallback(path) {
+            warn!(
+                "{} is a grid-only level file. Consider converting it to version {}",
+                path.display(),
+                CURRENT_LEVEL_VERSION
+            );
+            return Ok(LevelDocument::from_grid(v1::migrate_grid(grid)));
+        }
+
+        // report why the file is not a level of the current version, this is most likely what was intended
+        Err(error)
+    }
+
+    /// Wraps a bare grid into a document using the default `LevelInfo`.
+    pub fn from_grid(grid: Vec<Vec<Tile>>) -> LevelDocument {
+        LevelDocument {
+            version: CURRENT_LEVEL_VERSION,
+            info: LevelInfo::default(),
+            grid,
+        }
+    }
+
+    /// Rejects documents, that were written by a newer version of the game.
+    fn check_version(self) -> Result<LevelDocument, ConfigError> {
+        if self.version > CURRENT_LEVEL_VERSION {
+            return Err(ConfigError::File(io::Error::new(
+                io::ErrorKind::InvalidData,
+                format!(
+                    "level version {} is newer than the supported version {}",
+                    self.version, CURRENT_LEVEL_VERSION
+                ),
+            )));
+        }
+        Ok(self)
+    }
+}
+
+/// The format of version 1 and of grid-only files, where walls only knew whether they break.
+mod v1 {
+    use super::LevelInfo;
+    use entities::{Tile as CurrentTile, WallMaterial};
+
+    #[derive(Serialize, Deserialize)]
+    pub struct LevelDocument {
+        pub version: u32,
+        pub info: LevelInfo,
+        pub grid: Vec<Vec<Tile>>,
+    }
+
+    #[derive(Clone, Copy, Serialize, Deserialize)]
+    pub enum Tile {
+        Wall { breaks: bool, ore: u8 },
+        Ground { concealed: bool },
+        Any,
+    }
+
+    /// Breakable walls become loose rock, all other walls solid rock.
+    pub fn migrate_grid(grid: Vec<Vec<Tile>>) -> Vec<Vec<CurrentTile>> {
+        grid.into_iter()
+            .map(|column| {
+                column
+                    .into_iter()
+                    .map(|tile| match tile {
+                        Tile::Wall { breaks, ore } => CurrentTile::Wall {
+                            material: if breaks {
+                                WallMaterial::LooseRock
+                            } else {
+                                WallMaterial::SolidRock
+                            },
+                            ore,
+                        },
+                        Tile::Ground { concealed } => CurrentTile::Ground { concealed },
+                        Tile::Any => CurrentTile::Any,
+                    })
+                    .collect()
+            })
+            .collect()
+    }
+}
@@ -1,28 +1,43 @@
-const CONCEALED: &str = "concealed";
-
 use amethyst::{
     core::{
         nalgebra::{Point2, Vector3},
         transform::{GlobalTransform, Transform},
     },
     ecs::{
-        prelude::{Builder, Entity, World},
+        prelude::{Builder, Entities, Entity, World},
         storage::{GenericReadStorage, GenericWriteStorage, WriteStorage},
     },
 };
 
 use assetmanagement::util::*;
-use entities::Tile;
+use entities::{Ore, OreStorages, Tile};
 use eventhandling::{ClickHandlerComponent, HoverHandlerComponent, Hovered};
-use level::TilePatternMap;
-use pathfinding::directed::bfs;
-use systems::Path;
-use util;
+use level::{find_grid_path, neighborhood_of, TileLookupTable, CONCEALED};
+use rand::prelude::*;
+use systems::{Path, RevealQueue};
+
+use std::{
+    cmp::Reverse,
+    collections::{HashMap, VecDeque},
+    time::Duration,
+};
+
+/// The maximal distance from the middle of a drilled wall, at which its ore is dropped
+const ORE_SCATTER: f32 = 0.3;
+
+/// An event, that is published to the `EventChannel<TileChanged>` whenever a `Tile` of the `LevelGrid` changes, or something is built on it.
+#[derive(Clone, Copy, Debug)]
+pub struct TileChanged {
+    /// The entity of the changed `Tile`
+    pub tile: Entity,
+}
 
 /// A `Resource`, that holds every `Entity` that has a `Tile` Component and thus represents a part of the cave's layout
 pub struct LevelGrid {
     /// A two-dimensional array of the cave's geography.
     grid: Vec<Vec<Entity>>,
+    /// The grid positions, that buildings stand on, together with the entrance of the building. Covered positions can only be entered and left through the entrance.
+    covered: HashMap<(i32, i32), (i32, i32)>,
 }
 
 impl LevelGrid {
@@ -46,7 +61,10 @@ impl LevelGrid {
             })
             .collect();
 
-        LevelGrid { grid: level_grid }
+        LevelGrid {
+            grid: level_grid,
+            covered: HashMap::new(),
+        }
     }
 
     /// Returns the following tiles(marked as X), if existing:
@@ -87,53 +105,18 @@ impl LevelGrid {
         self.grid[0].len()
     }
 
-    /// Uses the surrounding of a `TIle` to determine the `Mesh` that  fits into. For more information see `TilePatternConfig`
+    /// Uses the surrounding of a `TIle` to determine the `Mesh` that  fits into. For more information see `TilePatternConfig` and `TileLookupTable`
     pub fn determine_sprite_for<'a, T: GenericReadStorage<Component = Tile>>(
         &self,
         x: i32,
         y: i32,
-        tile_patterns: &'a TilePatternMap,
+        lookup_table: &'a TileLookupTable,
         storage: &T,
     ) -> (&'a str, f32) {
-        let tile = self.get_tile(x as i32, y as i32, storage).unwrap();
-        if let Tile::Ground { concealed: true } = tile {
-            return (CONCEALED, 0.);
-        };
-        let mut key = [[Tile::default(); 3]; 3];
-        for delta_x in 0..3 {
-            for delta_y in 0..3 {
-                if let Some(t) =
-                    self.get_tile(x + delta_x as i32 - 1, y + delta_y as i32 - 1, storage)
-                {
-                    key[delta_x][delta_y] = *t;
-                    // if we get a None (aka out of bounds) we want a Tile::Default at this position.
-                    // nothing to do here because of the initialize with Tile::Defaults...
-                }
-            }
-        }
-
-        for rotation in 0..4 {
-            for (dict_key, value) in tile_patterns {
-                let mut pattern_match = true;
-                let dict_key = dict_key.iter().flatten();
-                let key = key.iter().flatten();
-
-                for (dict_tile, key_tile) in dict_key.zip(key) {
-                    if !dict_tile.pattern_eq(key_tile) {
-                        pattern_match = false;
-                        break;
-                    }
-                }
-                if pattern_match {
-                    return (
-                        value.as_str(),
-                        (rotation as f32) * std::f32::consts::PI / 2.,
-                    );
-                }
-            }
-            key = util::rotate_3x3(&key);
-        }
-        panic!("Cannot determine sprite for: {:?}", util::rotate_3x3(&key));
+        let neighborhood = neighborhood_of(x, y, |x, y| self.get_tile(x, y, storage).cloned());
+        lookup_table
+            .lookup(&neighborhood)
+            .unwrap_or_else(|| panic!("Cannot determine sprite for: {:?}", neighborhood))
     }
 
     /// determines and attaches the assets fitting the requested position the `Entity` at this position.
@@ -145,7 +128,7 @@ impl LevelGrid {
         &self,
         x: i32,
         y: i32,
-        dict: &TilePatternMap,
+        dict: &TileLookupTable,
         transforms: &mut R,
         tiles: &T,
         storages: &mut AssetStorages,
@@ -155,7 +138,13 @@ impl LevelGrid {
     ) {
         let entity = self.get(x, y).unwrap();
         let (classifier, rotation) = self.determine_sprite_for(x, y, &dict, tiles);
-        insert_into_asset_storages(entity, classifier, storages);
+
+        // visible walls show their material, walls deep inside the rock look like concealed ground
+        let texture = match self.get_tile(x, y, tiles) {
+            Some(Tile::Wall { material, .. }) if classifier != CONCEALED => material.texture_name(),
+            _ => classifier,
+        };
+        insert_into_asset_storages_with_texture(entity, classifier, texture, storages);
 
         let mut transform = Transform::default();
         transform.set_position(Vector3::new(x as f32, 0.0, y as f32));
@@ -196,6 +185,46 @@ impl LevelGrid {
         self.get(x, y).map(|entity| storage.get(entity).unwrap())
     }
 
+    /// Returns the grid position of the `Tile` that is nearest to the given translation.
+    pub fn grid_position(translation: &Vector3<f32>) -> (i32, i32) {
+        ((translation.x + 0.5) as i32, (translation.z + 0.5) as i32)
+    }
+
+    /// Turns the wall into revealed ground and drops the ore, that was inside the wall, on it. Returns the dropped `Ore` entities.
+    ///
+    /// The wall becomes concealed ground, that is queued to be revealed right away.
+    /// This way, the `GroundRevealSystem` updates the meshes around the former wall and reveals the concealed ground next to it, just like for any other revealed ground.
+    pub fn drill_wall(
+        &self,
+        wall: Entity,
+        tiles: &mut WriteStorage<Tile>,
+        reveal_queue: &mut RevealQueue,
+        now: Duration,
+        entities: &Entities,
+        ore_storages: &mut OreStorages,
+    ) -> Vec<Entity> {
+        let ore = match tiles.get(wall) {
+            Some(Tile::Wall { ore, .. }) => *ore,
+            _ => panic!("Only walls can be drilled"),
+        };
+
+        *tiles.get_mut(wall).unwrap() = Tile::Ground { concealed: true };
+        reveal_queue.push(Reverse((now, wall)));
+
+        let (x, y) = self.grid_position_of(&wall, &ore_storages.1);
+        let mut rng = rand::thread_rng();
+        (0..ore)
+            .map(|_| {
+                // scatter the ore a bit, so that it does not look like a single piece
+                let position = Point2::new(
+                    x as f32 + rng.gen_range(-ORE_SCATTER, ORE_SCATTER),
+                    y as f32 + rng.gen_range(-ORE_SCATTER, ORE_SCATTER),
+                );
+                Ore::instantiate(entities, position, ore_storages)
+            })
+            .collect()
+    }
+
     pub fn get(&self, x: i32, y: i32) -> Option<Entity> {
         if x < 0 || y < 0 || x >= self.grid.len() as i32 {
             return None;
@@ -208,7 +237,58 @@ impl LevelGrid {
             .map(|entity| *entity)
     }
 
-    /// Determines a `Path` from the start to the end Entity. Returns `None` if there is no Path.
+    /// Marks the grid positions as covered by a building, which is entered and left through the entrance.
+    pub fn cover(&mut self, positions: &[(i32, i32)], entrance: (i32, i32)) {
+        for &position in positions {
+            self.covered.insert(position, entrance);
+        }
+    }
+
+    /// Returns whether a building stands on the grid position or needs it as its entrance.
+    pub fn is_occupied(&self, x: i32, y: i32) -> bool {
+        self.covered.contains_key(&(x, y))
+            || self.covered.values().any(|&entrance| entrance == (x, y))
+    }
+
+    /// Returns the `Tile::movement_cost` for a straight step from one grid position onto the next one or `None` if the step cannot be taken.
+    ///
+    /// Besides onto `Tile`s that are not walkable themselves, this is the case for steps outside of the grid.
+    /// A building can only be entered and left through its entrance, so a step onto or off a position the building stands on has to come from or lead to the entrance.
+    /// Every search across the grid only takes the steps this allows. See `find_grid_path`
+    pub fn movement_cost<T: GenericReadStorage<Component = Tile>>(
+        &self,
+        from: (i32, i32),
+        to: (i32, i32),
+        tiles: &T,
+    ) -> Option<f32> {
+        let through_entrance = |position, other| {
+            self.covered
+                .get(&position)
+                .map_or(true, |&entrance| entrance == other)
+        };
+        if !through_entrance(from, to) || !through_entrance(to, from) {
+            return None;
+        }
+        self.get_tile(to.0, to.1, tiles)
+            .and_then(|tile| tile.movement_cost())
+    }
+
+    /// Returns whether the grid position can be stood on. Positions a building stands on cannot.
+    pub fn is_walkable<T: GenericReadStorage<Component = Tile>>(
+        &self,
+        x: i32,
+        y: i32,
+        tiles: &T,
+    ) -> bool {
+        !self.covered.contains_key(&(x, y))
+            && self
+                .get_tile(x, y, tiles)
+                .map_or(false, |tile| tile.is_walkable())
+    }
+
+    /// Determines the cheapest `Path` from the start to the end Entity. Returns `None` if there is no Path.
+    ///
+    /// The `Path` may go diagonally, but never cuts the corner of a wall. Every step is weighed with the `Tile::movement_cost` of the `Tile` it leads to.
     pub fn find_path<
         T: GenericReadStorage<Component = Tile>,
         TR: GenericReadStorage<Component = Transform>,
@@ -219,26 +299,95 @@ impl LevelGrid {
         tiles: &T,
         transforms: &TR,
     ) -> Option<Path> {
-        if let Some(result) = bfs::bfs(
-            &start,
-            |&entity| self.walkable_neighbors(&entity, tiles, transforms),
-            |&node| node == destination,
-        ) {
-            let result = result
-                .iter()
-                .map(|entity| {
-                    let position = self.grid_position_of(entity, transforms);
-                    Point2::new(position.0 as f32, position.1 as f32)
-                })
-                .collect();
-
-            return Some(Path { path: result });
-        };
+        let destination = self.grid_position_of(&destination, transforms);
+        self.find_path_to_nearest(start, &[destination], tiles, transforms)
+    }
+
+    /// Determines the cheapest `Path` from the start to any walkable `Tile` directly next to the target Entity. Returns `None` if there is no Path.
+    ///
+    /// This is used to walk up to `Tile`s that cannot be walked on themselves, like walls that are drilled.
+    pub fn find_path_to_neighbor_of<
+        T: GenericReadStorage<Component = Tile>,
+        TR: GenericReadStorage<Component = Transform>,
+    >(
+        &self,
+        start: Entity,
+        target: Entity,
+        tiles: &T,
+        transforms: &TR,
+    ) -> Option<Path> {
+        let (x, y) = self.grid_position_of(&target, transforms);
+        let destinations = [(x + 1, y), (x - 1, y), (x, y + 1), (x, y - 1)];
+        self.find_path_to_nearest(start, &destinations, tiles, transforms)
+    }
 
-        None
+    /// Determines the cheapest `Path` from the start to the nearest of the destinations. See `find_grid_path`
+    pub fn find_path_to_nearest<
+        T: GenericReadStorage<Component = Tile>,
+        TR: GenericReadStorage<Component = Transform>,
+    >(
+        &self,
+        start: Entity,
+        destinations: &[(i32, i32)],
+        tiles: &T,
+        transforms: &TR,
+    ) -> Option<Path> {
+        let start = self.grid_position_of(&start, transforms);
+        find_grid_path(start, destinations, |from, to| {
+            self.movement_cost(from, to, tiles)
+        })
+        .map(|(positions, _)| {
+            Path::new(
+                positions
+                    .into_iter()
+                    .map(|(x, y)| Point2::new(x as f32, y as f32))
+                    .collect(),
+            )
+        })
     }
 
-    /// Returns all direct neighbors that can be walked on
+    /// Returns up to `count` grid positions, that can be walked on, beginning with (x, y) itself and going outwards tile by tile.
+    ///
+    /// This is used to spread several entities over the `Tile`s around a destination, instead of sending them all onto the same `Tile`.
+    /// Only `Tile`s that are connected to (x, y) are returned, so there are less than `count`, if the area around (x, y) is too small. Nothing is spread into buildings.
+    pub fn spread_around<T: GenericReadStorage<Component = Tile>>(
+        &self,
+        x: i32,
+        y: i32,
+        count: usize,
+        tiles: &T,
+    ) -> Vec<(i32, i32)> {
+        let mut spread = vec![];
+        if !self.is_walkable(x, y, tiles) {
+            return spread;
+        }
+
+        let mut queue = VecDeque::new();
+        let mut visited = vec![(x, y)];
+        queue.push_back((x, y));
+        while let Some((x, y)) = queue.pop_front() {
+            if spread.len() == count {
+                break;
+            }
+            spread.push((x, y));
+
+            for (d_x, d_y) in [(0, 1), (0, -1), (1, 0), (-1, 0)].iter() {
+                let neighbor = (x + d_x, y + d_y);
+                if !visited.contains(&neighbor)
+                    && self.movement_cost((x, y), neighbor, tiles).is_some()
+                    && self.is_walkable(neighbor.0, neighbor.1, tiles)
+                {
+                    visited.push(neighbor);
+                    queue.push_back(neighbor);
+                }
+            }
+        }
+        spread
+    }
+
+    /// Returns all direct neighbors that can be stepped onto. See `movement_cost`
+    ///
+    /// A building can only be left through its entrance, so for a `Tile` a building stands on, this is the entrance at most.
     pub fn walkable_neighbors<
         T: GenericReadStorage<Component = Tile>,
         TR: GenericReadStorage<Component = Transform>,
@@ -248,26 +397,14 @@ impl LevelGrid {
         tiles: &T,
         transforms: &TR,
     ) -> Vec<Entity> {
-        let mut possible_neighbors = Vec::<Entity>::with_capacity(8);
-        let position = self.grid_position_of(entity, transforms);
-
-        // Note that the diagonals are not included. At hte moment, the movement is not weighed, so a diagonal path is as expensive as a linear walking when going linear
-        // thus, RockRaiders decide to go diagonal when they don't need to
-        // TODO #22
-        let mut directs = self.direct_neighbors(position.0, position.1);
-        possible_neighbors.append(&mut directs);
+        let (x, y) = self.grid_position_of(entity, transforms);
 
-        let result = possible_neighbors
+        [(0, 1), (0, -1), (1, 0), (-1, 0)]
             .iter()
-            .filter_map(|&entity| {
-                if tiles.get(entity).unwrap().is_walkable() {
-                    return Some(entity);
-                }
-                None
-            })
-            .collect();
-
-        result
+            .map(|(d_x, d_y)| (x + d_x, y + d_y))
+            .filter(|&neighbor| self.movement_cost((x, y), neighbor, tiles).is_some())
+            .filter_map(|(x, y)| self.get(x, y))
+            .collect()
     }
 
     /// Returns the position of the requested entity. Panics if the Entity is not part of the Grid
@@ -289,6 +426,9 @@ impl LevelGrid {
 
 impl Default for LevelGrid {
     fn default() -> LevelGrid {
-        LevelGrid { grid: vec![vec![]] }
+        LevelGrid {
+            grid: vec![vec![]],
+            covered: HashMap::new(),
+        }
     }
 }
@@ -1,11 +1,11 @@
 use amethyst::{
     assets::{AssetStorage, Loader},
     core::{
-        nalgebra::Vector3,
+        nalgebra::{Point2, Vector3},
         timing::Time,
         transform::{GlobalTransform, Parent, Transform},
     },
-    ecs::{Entity, WriteStorage},
+    ecs::{Entity, Join, WriteStorage},
     input::{is_close_requested, is_key_down, InputHandler},
     prelude::*,
     renderer::{
@@ -16,30 +16,73 @@ use amethyst::{
 };
 
 use assetmanagement::AssetManager;
-use entities::{buildings::Base, RockRaider, Tile};
-use eventhandling::{ClickHandlerComponent, GameEvent, HoverHandlerComponent, Hovered};
-use level::LevelGrid;
-use systems::{Oxygen, OxygenBar, Path, RevealQueue};
+use entities::{
+    buildings::{
+        check_start_footprint, Base, BuildingType, ConstructionSite, Facing, SupportStation,
+    },
+    Ore, RockRaider, Tile, WallMaterial,
+};
+use eventhandling::{
+    center_camera_on, to_window_space, ClickHandlerComponent, GameEvent, HoverHandlerComponent,
+    Hovered,
+};
+use level::{
+    ControlGroups, FlowFields, LevelDocument, LevelGrid, LevelInfo, TileLookupTable,
+    TilePatternMap, CONTROL_GROUPS,
+};
+use systems::{
+    AssignedJob, CarrySlot, Clear, CollectOre, Construct, Drill, FlowDestination, Health, JobBoard,
+    JobKind, MoveSpeed, OreStockpile, Oxygen, OxygenBar, Path, PlacementMode, RevealQueue,
+    SpawnQueue, TeleportOut,
+};
 use util::add_resource_soft;
 use GameScene;
 
 use std::{
     cmp::Reverse,
     ops::{Deref, DerefMut},
-    path::Path as OSPath,
+    path::{Path as OSPath, PathBuf},
 };
 
-pub struct SelectedRockRaider(pub Entity);
-
-impl Deref for SelectedRockRaider {
-    type Target = Entity;
+/// The distance in pixels, that the mouse has to be dragged with the left button down, before a selection box is drawn instead of clicking
+const DRAG_THRESHOLD: f64 = 4.;
+
+/// The keys that save and recall the `ControlGroups`, in the order of their numbers
+const DIGIT_KEYS: [VirtualKeyCode; CONTROL_GROUPS] = [
+    VirtualKeyCode::Key0,
+    VirtualKeyCode::Key1,
+    VirtualKeyCode::Key2,
+    VirtualKeyCode::Key3,
+    VirtualKeyCode::Key4,
+    VirtualKeyCode::Key5,
+    VirtualKeyCode::Key6,
+    VirtualKeyCode::Key7,
+    VirtualKeyCode::Key8,
+    VirtualKeyCode::Key9,
+];
+
+/// The keys that raise the `Priority` of the `JobKind`s, in the order of `JobKind::all`
+const PRIORITY_KEYS: [VirtualKeyCode; 5] = [
+    VirtualKeyCode::F1,
+    VirtualKeyCode::F2,
+    VirtualKeyCode::F3,
+    VirtualKeyCode::F4,
+    VirtualKeyCode::F5,
+];
+
+/// The `RockRaider`s that receive the next order
+#[derive(Default)]
+pub struct SelectedRockRaiders(pub Vec<Entity>);
+
+impl Deref for SelectedRockRaiders {
+    type Target = Vec<Entity>;
 
     fn deref(&self) -> &Self::Target {
         &self.0
     }
 }
 
-impl DerefMut for SelectedRockRaider {
+impl DerefMut for SelectedRockRaiders {
     fn deref_mut(&mut self) -> &mut Self::Target {
         &mut self.0
     }
@@ -47,25 +90,21 @@ impl DerefMut for SelectedRockRaider {
 
 /// The `State` that is active, when a level runs
 pub struct LevelState {
-    /// pretty self explanatory
-    pub mouse_button_was_down: bool,
+    /// The file the level is loaded from
+    level_path: PathBuf,
+    /// The window position, at which the left mouse button was pressed, as long as it is held down
+    drag_start: Option<(f64, f64)>,
 }
 
-/// This is a Map referencing from a 3x3 Tile matrix to a String.
-/// The 3x3 describes a pattern (for example 9 times a `Tile::Wall`) that can occur in the cave's layout and maps to the asset's name that is representing it:
-///
-/// example:
-/// ```
-/// (
-///  [[Ground, Ground, Ground],
-///   [Wall,   Wall,   Wall],
-///   [Wall,   Wall,   Wall]]
-///  , "straight_wall"
-/// ```
-///
-pub type TilePatternMap = Vec<([[Tile; 3]; 3], String)>;
-
 impl LevelState {
+    /// Creates the state for the level stored at the given path. The level is loaded when the state starts.
+    pub fn new(level_path: PathBuf) -> LevelState {
+        LevelState {
+            level_path,
+            drag_start: None,
+        }
+    }
+
     /// Loads the `TilePatternMap` from disk.
     fn load_tile_pattern_config() -> TilePatternMap {
         let result = TilePatternMap::load(OSPath::new(&format!(
@@ -77,15 +116,14 @@ impl LevelState {
         result
     }
 
-    /// Loads the cave's model from disk.
-    fn load_tile_grid() -> Vec<Vec<Tile>> {
-        let tile_grid = Vec::<Vec<Tile>>::load(OSPath::new(&format!(
-            "{}/assets/levels/1.ron",
-            env!("CARGO_MANIFEST_DIR")
-        )));
+    /// Loads the level's document from disk.
+    fn load_level(&self) -> LevelDocument {
+        let document = LevelDocument::load_from(&self.level_path).unwrap_or_else(|error| {
+            panic!("Cannot load level {}: {}", self.level_path.display(), error)
+        });
 
-        debug!("Loaded Grid successfully");
-        tile_grid
+        debug!("Loaded level {} successfully", document.info.name);
+        document
     }
 
     /// Converts the cave's model into a `LevelGrid` and adds it to the world.
@@ -96,7 +134,7 @@ impl LevelState {
         {
             let tiles = world.read_storage::<Tile>();
             let mut transforms = world.write_storage::<Transform>();
-            let dict = world.read_resource::<TilePatternMap>();
+            let dict = world.read_resource::<TileLookupTable>();
             let mut storages = world.system_data();
             let mut hover_storage = world.system_data::<WriteStorage<HoverHandlerComponent>>();
             let mut click_storage = world.system_data::<WriteStorage<ClickHandlerComponent>>();
@@ -122,6 +160,7 @@ impl LevelState {
     }
 
     /// Loads all assets that will presumably be used in the level into memory and `AssetManager`.
+    /// This includes the textures of every `WallMaterial`.
     fn load_initial_assets(world: &World) {
         let mut mesh_manager = world.write_resource::<AssetManager<Mesh>>();
         let mut mesh_storage = world.write_resource::<AssetStorage<Mesh>>();
@@ -146,6 +185,17 @@ impl LevelState {
                 &loader,
             );
         }
+
+        for material in WallMaterial::all().iter() {
+            debug!("loading texture: {}", material.texture_name());
+            texture_manager.get_asset_handle_or_load(
+                material.texture_name(),
+                PngFormat,
+                TextureMetadata::srgb(),
+                &mut texture_storage,
+                &loader,
+            );
+        }
     }
 
     /// Creates an camera entity and sets it as `Activecamera`.
@@ -192,9 +242,22 @@ impl LevelState {
             .build();
     }
 
-    /// Creates a `Base` for the Level.
+    /// Creates a `Base` for the Level at the position the `LevelInfo` requests, together with the `RockRaider`s the level starts with.
     fn initialize_base(world: &mut World) {
-        let entity = world.read_resource::<LevelGrid>().get(2, 0).unwrap();
+        let (x, y) = world.read_resource::<LevelInfo>().base_position;
+        // the footprint is revealed together with the `Base`, so it may still be concealed
+        let footprint = {
+            let level_grid = world.read_resource::<LevelGrid>();
+            let tiles = world.read_storage::<Tile>();
+            check_start_footprint(BuildingType::Base, x, y, Facing::default(), |x, y| {
+                level_grid.get_tile(x, y, &tiles).cloned()
+            })
+        };
+        if let Err(error) = footprint {
+            warn!("Cannot build the Base at ({}, {}): {}", x, y, error);
+            return;
+        }
+        let entity = world.read_resource::<LevelGrid>().get(x, y).unwrap();
         {
             if let Some(ref mut queue) = *world.write_resource::<Option<RevealQueue>>() {
                 queue.push(Reverse((
@@ -202,7 +265,261 @@ impl LevelState {
                     entity,
                 )));
             }
-            Base::build(&entity, world);
+            BuildingType::Base.build(x, y, Facing::default(), world);
+        }
+
+        let raiders = world.read_resource::<LevelInfo>().raiders.clone();
+        for (x, y) in raiders {
+            let storages = world.system_data();
+            let hover_storage = world.system_data();
+            let click_storage = world.system_data();
+            let entities = world.entities();
+            RockRaider::instantiate(
+                &entities,
+                Point2::new(x as f32, y as f32),
+                storages,
+                hover_storage,
+                click_storage,
+            );
+        }
+    }
+
+    /// Selects every `RockRaider`, that is shown inside the box between the two window positions.
+    fn select_in_box(world: &World, (start_x, start_y): (f64, f64), (end_x, end_y): (f64, f64)) {
+        let (min_x, max_x) = (start_x.min(end_x) as f32, start_x.max(end_x) as f32);
+        let (min_y, max_y) = (start_y.min(end_y) as f32, start_y.max(end_y) as f32);
+
+        let active_camera = world.read_resource::<ActiveCamera>().entity;
+        let dimensions = world.read_resource::<ScreenDimensions>();
+        let transforms = world.read_storage::<Transform>();
+        let (proj, view) = match (
+            world.read_storage::<Camera>().get(active_camera),
+            transforms.get(active_camera),
+        ) {
+            (Some(camera), Some(transform)) => (camera.proj, transform.matrix()),
+            _ => return,
+        };
+
+        let selected = (
+            &world.entities(),
+            &world.read_storage::<RockRaider>(),
+            &transforms,
+        )
+            .join()
+            .filter(|(_, _, transform)| {
+                to_window_space(
+                    transform.translation(),
+                    (dimensions.width(), dimensions.height()),
+                    proj,
+                    view,
+                )
+                .map_or(false, |(x, y)| {
+                    min_x <= x && x <= max_x && min_y <= y && y <= max_y
+                })
+            })
+            .map(|(entity, _, _)| entity)
+            .collect();
+        **world.write_resource::<SelectedRockRaiders>() = selected;
+    }
+
+    /// Saves the selected `RockRaider`s as the control group, if Ctrl is held, or selects the control group otherwise.
+    /// Selecting the same group twice in a row centers the camera on it.
+    fn handle_control_group(world: &World, group: usize) {
+        let save = {
+            let input = world.read_resource::<InputHandler<String, String>>();
+            input.key_is_down(VirtualKeyCode::LControl)
+                || input.key_is_down(VirtualKeyCode::RControl)
+        };
+
+        let entities = world.entities();
+        let rock_raiders = world.read_storage::<RockRaider>();
+        let mut control_groups = world.write_resource::<ControlGroups>();
+
+        if save {
+            let selected = world.read_resource::<SelectedRockRaiders>();
+            control_groups.save(group, &selected, &entities, &rock_raiders);
+            return;
+        }
+
+        let now = world.read_resource::<Time>().absolute_real_time_seconds();
+        let (selected, double_tap) = control_groups.recall(group, now, &entities, &rock_raiders);
+
+        if double_tap && !selected.is_empty() {
+            let mut transforms = world.write_storage::<Transform>();
+            let center = selected
+                .iter()
+                .map(|&entity| *transforms.get(entity).unwrap().translation())
+                .fold(Vector3::zeros(), |sum, position| sum + position)
+                / selected.len() as f32;
+
+            let camera = world.read_resource::<ActiveCamera>().entity;
+            if let Some(camera) = transforms.get_mut(camera) {
+                center_camera_on(camera, &center);
+            }
+        }
+        **world.write_resource::<SelectedRockRaiders>() = selected;
+    }
+
+    /// Starts placing the building, or stops placing, if the player places a building already.
+    fn toggle_placement_mode(world: &World, building: BuildingType) {
+        let placing = world.write_resource::<Option<PlacementMode>>().take();
+        match placing {
+            Some(mode) => mode.stop(world),
+            None => {
+                let mode = PlacementMode::start(building, world);
+                *world.write_resource::<Option<PlacementMode>>() = Some(mode);
+            }
+        }
+    }
+
+    /// Replaces every `ConstructionSite`, whose building is finished, with the building itself.
+    fn complete_construction_sites(world: &World) {
+        let finished: Vec<(Entity, ConstructionSite)> =
+            (&world.entities(), &world.read_storage::<ConstructionSite>())
+                .join()
+                .filter(|(_, site)| site.is_finished())
+                .map(|(entity, site)| (entity, site.clone()))
+                .collect();
+
+        for (entity, site) in finished {
+            // the site is not deleted before the next frame, so it must not be found as finished again
+            world.write_storage::<ConstructionSite>().remove(entity);
+            world.entities().delete(entity).unwrap();
+
+            let (x, y) = site.position;
+            site.building.build(x, y, site.facing, world);
+            info!("{:?} built at ({}, {})", site.building, x, y);
+        }
+    }
+
+    /// Spawns the next `RockRaider` of every `SpawnQueue`, that finished teleporting in.
+    /// A `Base` without a free `Tile` next to it keeps the `RockRaider` in its queue, until there is one.
+    fn spawn_queued_rock_raiders(world: &World) {
+        let ready: Vec<Entity> = (
+            &world.entities(),
+            &world.read_storage::<Base>(),
+            &world.read_storage::<SpawnQueue>(),
+        )
+            .join()
+            .filter(|(_, _, queue)| queue.is_ready())
+            .map(|(entity, _, _)| entity)
+            .collect();
+
+        for base in ready {
+            if Base::spawn_rock_raider(base, world).is_some() {
+                let mut queues = world.write_storage::<SpawnQueue>();
+                let queue = queues.get_mut(base).unwrap();
+                queue.queued -= 1;
+                queue.progress = 0.;
+            }
+        }
+    }
+
+    /// Creates the ui element, that shows the length of the `SpawnQueue`, for every `Base` that has none yet.
+    fn create_spawn_queue_labels(world: &mut World) {
+        let unlabeled: Vec<Entity> = (&world.entities(), &world.read_storage::<SpawnQueue>())
+            .join()
+            .filter(|(_, queue)| queue.label.is_none())
+            .map(|(entity, _)| entity)
+            .collect();
+
+        for base in unlabeled {
+            let label = world
+                .exec(|mut creator: UiCreator| creator.create("ui/spawn_queue/prefab.ron", ()));
+            world
+                .write_storage::<SpawnQueue>()
+                .get_mut(base)
+                .unwrap()
+                .label = Some(label);
+        }
+    }
+
+    /// Makes the hovered `Tile` the rally point of every `Base`. New `RockRaider`s walk there, as soon as they are spawned.
+    fn set_rally_point(world: &World) {
+        let tile = match **world.read_resource::<Hovered>() {
+            Some(entity) if world.read_storage::<Tile>().get(entity).is_some() => entity,
+            _ => {
+                warn!("Cannot set the rally point: no Tile is hovered");
+                return;
+            }
+        };
+        let (x, y) = LevelGrid::grid_position(
+            world
+                .read_storage::<Transform>()
+                .get(tile)
+                .unwrap()
+                .translation(),
+        );
+        if !world
+            .read_resource::<LevelGrid>()
+            .is_walkable(x, y, &world.read_storage::<Tile>())
+        {
+            warn!(
+                "Cannot set the rally point: ({}, {}) cannot be walked on",
+                x, y
+            );
+            return;
+        }
+
+        for queue in (&mut world.write_storage::<SpawnQueue>()).join() {
+            queue.rally_point = Some((x, y));
+        }
+        info!("Rally point set to ({}, {})", x, y);
+    }
+
+    /// Orders the selected `RockRaider`s to walk to the `Base` and teleport out. This cancels their other orders.
+    fn teleport_out_selected(world: &World) {
+        let base_tile = (
+            &world.read_storage::<Base>(),
+            &world.read_storage::<Parent>(),
+        )
+            .join()
+            .map(|(_, parent)| parent.entity)
+            .next();
+        let base_tile = match base_tile {
+            Some(base_tile) => base_tile,
+            None => {
+                warn!("There is no Base to teleport out through");
+                return;
+            }
+        };
+
+        let selected: Vec<Entity> = world
+            .write_resource::<SelectedRockRaiders>()
+            .drain(..)
+            .collect();
+        let level_grid = world.read_resource::<LevelGrid>();
+        let tiles = world.read_storage::<Tile>();
+        let transforms = world.read_storage::<Transform>();
+
+        for rock_raider in selected {
+            let start = match transforms.get(rock_raider) {
+                Some(transform) => {
+                    let (x, y) = LevelGrid::grid_position(transform.translation());
+                    level_grid.get(x, y).unwrap()
+                }
+                // the `RockRaider` is gone already
+                None => continue,
+            };
+
+            if let Some(path) =
+                level_grid.find_path_to_neighbor_of(start, base_tile, &tiles, &transforms)
+            {
+                world.write_storage::<AssignedJob>().remove(rock_raider);
+                world.write_storage::<Drill>().remove(rock_raider);
+                world.write_storage::<Clear>().remove(rock_raider);
+                world.write_storage::<CollectOre>().remove(rock_raider);
+                Construct::cancel(rock_raider, world);
+                world.write_storage::<FlowDestination>().remove(rock_raider);
+                world
+                    .write_storage::<Path>()
+                    .insert(rock_raider, path)
+                    .unwrap();
+                world
+                    .write_storage::<TeleportOut>()
+                    .insert(rock_raider, TeleportOut)
+                    .unwrap();
+            }
         }
     }
 
@@ -218,32 +535,57 @@ impl<'a, 'b> State<GameData<'a, 'b>, GameEvent> for LevelState {
         world.register::<Tile>();
         world.register::<Light>();
         world.register::<Base>();
+        world.register::<SupportStation>();
+        world.register::<ConstructionSite>();
         world.register::<HoverHandlerComponent>();
         world.register::<ClickHandlerComponent>();
         world.register::<RockRaider>();
         world.register::<Path>();
+        world.register::<FlowDestination>();
+        world.register::<Drill>();
+        world.register::<Clear>();
+        world.register::<Ore>();
+        world.register::<CollectOre>();
+        world.register::<Construct>();
+        world.register::<SpawnQueue>();
+        world.register::<Health>();
+        world.register::<MoveSpeed>();
+        world.register::<AssignedJob>();
+        world.register::<CarrySlot>();
+        world.register::<TeleportOut>();
 
         let mesh_manager = AssetManager::<Mesh>::default();
         let texture_manager = AssetManager::<Texture>::default();
         let tile_pattern_config = LevelState::load_tile_pattern_config();
-        let oxygen = Oxygen::new(100.);
+        let level = self.load_level();
+        let oxygen = Oxygen::new(level.info.oxygen);
 
         world.exec(|mut creator: UiCreator| creator.create("ui/oxygen_bar/prefab.ron", ()));
 
         world.add_resource(Some(RevealQueue::new()));
         world.add_resource(Some(oxygen));
+        world.add_resource(level.info);
+        world.add_resource(OreStockpile::default());
+        // the flow fields of another level would not fit this grid
+        world.add_resource(FlowFields::default());
         world.add_resource::<Hovered>(Hovered::default());
         world.add_resource::<Option<OxygenBar>>(None);
-        world.add_resource::<Option<SelectedRockRaider>>(None);
+        world.add_resource(SelectedRockRaiders::default());
+        world.add_resource(ControlGroups::default());
+        world.add_resource(JobBoard::default());
+        world.add_resource::<Option<PlacementMode>>(None);
 
         add_resource_soft(world, mesh_manager);
         add_resource_soft(world, texture_manager);
         add_resource_soft(world, tile_pattern_config);
+        let lookup_table = TileLookupTable::new(&world.read_resource::<TilePatternMap>());
+        world.add_resource(lookup_table);
 
         LevelState::load_initial_assets(world);
         let cam = LevelState::initialize_camera(world);
         LevelState::initialize_light(world, cam);
-        LevelState::initialize_level_grid(world, LevelState::load_tile_grid());
+        LevelState::initialize_level_grid(world, level.grid);
+        LevelState::initialize_base(world);
 
         *world.write_resource() = LevelState::scene();
     }
@@ -267,9 +609,37 @@ impl<'a, 'b> State<GameData<'a, 'b>, GameEvent> for LevelState {
                 } else if is_key_down(&event, VirtualKeyCode::Tab) {
                     debug!("Leaving Level State");
                     return Trans::Pop;
-                } else if is_key_down(&event, VirtualKeyCode::Space) {
-                    do_test_method(world);
-
+                } else if is_key_down(&event, VirtualKeyCode::B) {
+                    LevelState::toggle_placement_mode(world, BuildingType::Base);
+                    return Trans::None;
+                } else if is_key_down(&event, VirtualKeyCode::S) {
+                    LevelState::toggle_placement_mode(world, BuildingType::SupportStation);
+                    return Trans::None;
+                } else if is_key_down(&event, VirtualKeyCode::R) {
+                    if let Some(ref mut mode) = *world.write_resource::<Option<PlacementMode>>() {
+                        mode.rotate();
+                    }
+                    return Trans::None;
+                } else if is_key_down(&event, VirtualKeyCode::P) {
+                    LevelState::set_rally_point(world);
+                    return Trans::None;
+                } else if is_key_down(&event, VirtualKeyCode::T) {
+                    LevelState::teleport_out_selected(world);
+                    return Trans::None;
+                } else if let Some(group) =
+                    DIGIT_KEYS.iter().position(|&key| is_key_down(&event, key))
+                {
+                    LevelState::handle_control_group(world, group);
+                    return Trans::None;
+                } else if let Some(index) = PRIORITY_KEYS
+                    .iter()
+                    .position(|&key| is_key_down(&event, key))
+                {
+                    let kind = JobKind::all()[index];
+                    let mut job_board = world.write_resource::<JobBoard>();
+                    let priority = job_board.priority(kind).next();
+                    job_board.set_priority(kind, priority);
+                    info!("Priority of {:?} jobs: {:?}", kind, priority);
                     return Trans::None;
                 }
             }
@@ -280,30 +650,54 @@ impl<'a, 'b> State<GameData<'a, 'b>, GameEvent> for LevelState {
                 // # add handler again
                 // This is required, because the handler itself may fetch the hoverhandler storage on execution, what would lead to a new borrow, while this method still borrows the storage to execute the handler.
                 // To bypass this, we remove the handler for the time of execution, so that no resource of the world is borrowed and there are no possible `Invalid Borrow` clashes from this side of the code.
-                let mut hover_handler = world
+                // The handler may be gone already, if the entity was deleted while it was hovered.
+                let hover_handler = world
                     .write_storage::<HoverHandlerComponent>()
-                    .remove(event.target)
-                    .unwrap();
-                if event.start {
-                    // hover started
-                    hover_handler.on_hover_start(event.target, world);
-                } else {
-                    // hover ended
-                    hover_handler.on_hover_stop(event.target, world);
+                    .remove(event.target);
+                if let Some(mut hover_handler) = hover_handler {
+                    if event.start {
+                        // hover started
+                        hover_handler.on_hover_start(event.target, world);
+                    } else {
+                        // hover ended
+                        hover_handler.on_hover_stop(event.target, world);
+                    }
+                    world
+                        .write_storage::<HoverHandlerComponent>()
+                        .insert(event.target, hover_handler)
+                        .unwrap();
                 }
-                world
-                    .write_storage::<HoverHandlerComponent>()
-                    .insert(event.target, hover_handler)
-                    .unwrap();
             }
             _ => (),
         }
 
-        let mouse_button = world
-            .read_resource::<InputHandler<String, String>>()
-            .mouse_button_is_down(MouseButton::Left);
+        let (mouse_button, mouse_position) = {
+            let input = world.read_resource::<InputHandler<String, String>>();
+            (
+                input.mouse_button_is_down(MouseButton::Left),
+                input.mouse_position(),
+            )
+        };
+
+        // a click happens, when the button is released without dragging the mouse
+        let mut clicked = false;
+        match (self.drag_start, mouse_button, mouse_position) {
+            (None, true, Some(position)) => self.drag_start = Some(position),
+            (Some(start), false, Some(end)) => {
+                self.drag_start = None;
+                if (end.0 - start.0).abs() > DRAG_THRESHOLD
+                    || (end.1 - start.1).abs() > DRAG_THRESHOLD
+                {
+                    LevelState::select_in_box(world, start, end);
+                } else {
+                    clicked = true;
+                }
+            }
+            (Some(_), false, None) => self.drag_start = None,
+            _ => (),
+        }
 
-        if !self.mouse_button_was_down && mouse_button {
+        if clicked {
             if let Some(entity) = **(world.read_resource::<Hovered>()) {
                 // see hover event dispatching
                 let opt_handler = world
@@ -317,14 +711,17 @@ impl<'a, 'b> State<GameData<'a, 'b>, GameEvent> for LevelState {
                 });
             }
         }
-        self.mouse_button_was_down = mouse_button;
 
-        // reset selection on right click
+        // reset selection and stop placing buildings on right click
         if world
             .read_resource::<InputHandler<String, String>>()
             .mouse_button_is_down(MouseButton::Right)
         {
-            *world.write_resource::<Option<SelectedRockRaider>>() = None;
+            world.write_resource::<SelectedRockRaiders>().clear();
+            let placing = world.write_resource::<Option<PlacementMode>>().take();
+            if let Some(mode) = placing {
+                mode.stop(world);
+            }
         }
         Trans::None
     }
@@ -332,7 +729,10 @@ impl<'a, 'b> State<GameData<'a, 'b>, GameEvent> for LevelState {
     fn on_stop(&mut self, data: StateData<GameData>) {
         let world = data.world;
         *world.write_resource() = GameScene::default();
-        *world.write_resource::<Option<SelectedRockRaider>>() = None;
+        world.write_resource::<SelectedRockRaiders>().clear();
+        *world.write_resource::<ControlGroups>() = ControlGroups::default();
+        *world.write_resource::<JobBoard>() = JobBoard::default();
+        *world.write_resource::<Option<PlacementMode>>() = None;
         **world.write_resource::<Hovered>() = None;
         *world.write_resource::<Option<OxygenBar>>() = None;
         *world.write_resource::<Option<Oxygen>>() = None;
@@ -344,10 +744,9 @@ impl<'a, 'b> State<GameData<'a, 'b>, GameEvent> for LevelState {
     }
     fn update(&mut self, data: StateData<GameData>) -> Trans<GameData<'a, 'b>, GameEvent> {
         data.data.update(&data.world);
+        LevelState::complete_construction_sites(&data.world);
+        LevelState::spawn_queued_rock_raiders(&data.world);
+        LevelState::create_spawn_queue_labels(data.world);
         Trans::None
     }
 }
-
-fn do_test_method(world: &mut World) {
-    LevelState::initialize_base(world);
-}
@@ -1,7 +1,21 @@
+mod control_groups;
+mod flow_fields;
+mod level_document;
 mod level_grid;
 mod level_state;
+mod path_search;
+mod tile_pattern;
+mod validation;
 
-pub use self::level_grid::LevelGrid;
+pub use self::control_groups::{ControlGroups, CONTROL_GROUPS};
+pub use self::flow_fields::FlowFields;
+pub use self::level_document::{LevelDocument, LevelInfo, Objective, OxygenRates};
+pub use self::level_grid::{LevelGrid, TileChanged};
 pub use self::level_state::LevelState;
-pub use self::level_state::SelectedRockRaider;
-pub use self::level_state::TilePatternMap;
+pub use self::level_state::SelectedRockRaiders;
+pub use self::path_search::{find_grid_path, FlowField, DIAGONAL_STEP_COST, STRAIGHT_STEP_COST};
+pub use self::tile_pattern::{
+    check_coverage, find_sprite, neighborhood_of, Neighborhood, PatternCoverage, TileLookupTable,
+    TilePatternMap, CONCEALED,
+};
+pub use self::validation::{validate_level, LevelProblem};
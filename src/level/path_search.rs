@@ -0,0 +1,234 @@
+use pathfinding::directed::astar;
+
+use std::{cmp::Reverse, collections::BinaryHeap};
+
+/// The cost of a straight step onto a `Tile`, whose `Tile::movement_cost` is 1.
+/// Costs are integers, because the search needs them to be totally ordered.
+pub const STRAIGHT_STEP_COST: u32 = 100;
+
+/// The cost of a diagonal step onto a `Tile`, whose `Tile::movement_cost` is 1. This is √2 times a straight step.
+pub const DIAGONAL_STEP_COST: u32 = 141;
+
+/// Searches the cheapest path from `start` to any of the `destinations` using A*. Paths may go in all eight directions.
+///
+/// `movement_cost` returns the `Tile::movement_cost` for a straight step from the first position onto the second one or `None` if that step cannot be taken, e.g. because the second position cannot be walked on or is outside of the grid.
+/// Between two positions, that can both be walked on, whether a step can be taken must not depend on its direction. See `LevelGrid::movement_cost`
+/// A diagonal step is only taken, if it can be taken as two straight steps in either order as well, so that a path never cuts the corner of a wall or a building.
+///
+/// Returns the positions of the path, including `start`, together with the cost of the path.
+pub fn find_grid_path<F: Fn((i32, i32), (i32, i32)) -> Option<f32>>(
+    start: (i32, i32),
+    destinations: &[(i32, i32)],
+    movement_cost: F,
+) -> Option<(Vec<(i32, i32)>, u32)> {
+    astar::astar(
+        &start,
+        |&(x, y)| steps_from(x, y, &movement_cost),
+        |&position| estimate_cost(position, destinations),
+        |position| destinations.contains(position),
+    )
+}
+
+/// Returns every position that can be reached from (x, y) with a single step, together with the cost of that step.
+fn steps_from<F: Fn((i32, i32), (i32, i32)) -> Option<f32>>(
+    x: i32,
+    y: i32,
+    movement_cost: &F,
+) -> Vec<((i32, i32), u32)> {
+    let mut steps = Vec::with_capacity(8);
+    for delta_x in -1..=1 {
+        for delta_y in -1..=1 {
+            if (delta_x, delta_y) == (0, 0) {
+                continue;
+            }
+            let target = (x + delta_x, y + delta_y);
+            let cost = match movement_cost((x, y), target) {
+                Some(cost) => cost,
+                None => continue,
+            };
+            if delta_x != 0
+                && delta_y != 0
+                && [(x + delta_x, y), (x, y + delta_y)].iter().any(|&corner| {
+                    movement_cost((x, y), corner).is_none()
+                        || movement_cost(corner, target).is_none()
+                })
+            {
+                continue;
+            }
+
+            steps.push((target, step_cost(delta_x, delta_y, cost)));
+        }
+    }
+    steps
+}
+
+/// The cost of a single step in the direction (delta_x, delta_y) onto a `Tile` with the given `Tile::movement_cost`.
+fn step_cost(delta_x: i32, delta_y: i32, movement_cost: f32) -> u32 {
+    let step_cost = if delta_x != 0 && delta_y != 0 {
+        DIAGONAL_STEP_COST
+    } else {
+        STRAIGHT_STEP_COST
+    };
+    (step_cost as f32 * movement_cost).round() as u32
+}
+
+/// The octile distance to the nearest destination.
+/// It never overestimates the actual cost, because no `Tile` is cheaper to cross than plain ground.
+fn estimate_cost(position: (i32, i32), destinations: &[(i32, i32)]) -> u32 {
+    destinations
+        .iter()
+        .map(|&(x, y)| {
+            let delta_x = (x - position.0).abs() as u32;
+            let delta_y = (y - position.1).abs() as u32;
+            let diagonal = delta_x.min(delta_y);
+            let straight = delta_x.max(delta_y) - diagonal;
+            diagonal * DIAGONAL_STEP_COST + straight * STRAIGHT_STEP_COST
+        })
+        .min()
+        .unwrap_or(0)
+}
+
+/// The cheapest paths from every position of the grid to the nearest of some destinations.
+///
+/// It is computed once with Dijkstra's algorithm, starting at the destinations. Afterwards any number of entities can look up their next step, without searching a path of their own.
+/// The steps follow the same rules as `find_grid_path`.
+pub struct FlowField {
+    width: usize,
+    height: usize,
+    /// For every position the cost of its cheapest path and the next position on that path, which is `None` for the destinations themselves
+    cells: Vec<Option<(u32, Option<(i32, i32)>)>>,
+}
+
+impl FlowField {
+    /// `movement_cost` is the same as for `find_grid_path`. Destinations, that cannot be stepped onto from any position next to them, are ignored.
+    pub fn new<F: Fn((i32, i32), (i32, i32)) -> Option<f32>>(
+        destinations: &[(i32, i32)],
+        width: usize,
+        height: usize,
+        movement_cost: F,
+    ) -> FlowField {
+        let mut field = FlowField {
+            width,
+            height,
+            cells: vec![None; width * height],
+        };
+
+        let mut queue = BinaryHeap::new();
+        for &(x, y) in destinations {
+            let reachable = steps_from(x, y, &movement_cost)
+                .iter()
+                .any(|&(neighbor, _)| movement_cost(neighbor, (x, y)).is_some());
+            if let Some(index) = field.index(x, y).filter(|_| reachable) {
+                field.cells[index] = Some((0, None));
+                queue.push(Reverse((0, (x, y))));
+            }
+        }
+
+        while let Some(Reverse((cost, (x, y)))) = queue.pop() {
+            // the position was reached cheaper in the meantime
+            if field.cost(x, y).map_or(false, |known| known < cost) {
+                continue;
+            }
+
+            // the steps do not depend on their direction, so every position that can be reached from here, can step onto this position as well
+            for ((neighbor_x, neighbor_y), _) in steps_from(x, y, &movement_cost) {
+                let index = match field.index(neighbor_x, neighbor_y) {
+                    Some(index) => index,
+                    None => continue,
+                };
+                let entering_cost = movement_cost((neighbor_x, neighbor_y), (x, y)).unwrap();
+                let neighbor_cost = cost + step_cost(x - neighbor_x, y - neighbor_y, entering_cost);
+                if field.cells[index].map_or(true, |(known, _)| neighbor_cost < known) {
+                    field.cells[index] = Some((neighbor_cost, Some((x, y))));
+                    queue.push(Reverse((neighbor_cost, (neighbor_x, neighbor_y))));
+                }
+            }
+        }
+        field
+    }
+
+    /// Returns the cost of the cheapest path from (x, y) to the nearest destination or `None`, if no destination can be reached from there.
+    pub fn cost(&self, x: i32, y: i32) -> Option<u32> {
+        self.index(x, y)
+            .and_then(|index| self.cells[index])
+            .map(|(cost, _)| cost)
+    }
+
+    /// Returns the position to step onto from (x, y) or `None`, if (x, y) is a destination or no destination can be reached from there.
+    pub fn next_step(&self, x: i32, y: i32) -> Option<(i32, i32)> {
+        self.index(x, y)
+            .and_then(|index| self.cells[index])
+            .and_then(|(_, next)| next)
+    }
+
+    fn index(&self, x: i32, y: i32) -> Option<usize> {
+        if x < 0 || y < 0 || x as usize >= self.width || y as usize >= self.height {
+            return None;
+        }
+        Some(x as usize * self.height + y as usize)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// The `movement_cost` of a grid, whose rows are given from top to bottom: `.` is ground, `r` is rubble and `#` is a wall
+    fn grid(rows: &'static [&'static str]) -> impl Fn((i32, i32), (i32, i32)) -> Option<f32> {
+        move |_, (x, y)| {
+            if x < 0 || y < 0 {
+                return None;
+            }
+            rows.get(y as usize)
+                .and_then(|row| row.as_bytes().get(x as usize))
+                .and_then(|cell| match *cell {
+                    b'.' => Some(1.),
+                    b'r' => Some(2.),
+                    _ => None,
+                })
+        }
+    }
+
+    #[test]
+    fn finds_the_cheapest_path() {
+        let (path, cost) =
+            find_grid_path((0, 0), &[(4, 2)], grid(&[".....", ".....", "....."])).unwrap();
+        assert_eq!(path.len(), 5);
+        assert_eq!(path.first(), Some(&(0, 0)));
+        assert_eq!(path.last(), Some(&(4, 2)));
+        assert_eq!(cost, 2 * DIAGONAL_STEP_COST + 2 * STRAIGHT_STEP_COST);
+    }
+
+    #[test]
+    fn does_not_cut_the_corner_of_a_wall() {
+        let (path, cost) = find_grid_path((0, 0), &[(1, 1)], grid(&[".#", ".."])).unwrap();
+        assert_eq!(path, vec![(0, 0), (0, 1), (1, 1)]);
+        assert_eq!(cost, 2 * STRAIGHT_STEP_COST);
+    }
+
+    #[test]
+    fn rubble_costs_more_than_ground() {
+        let (_, cost) = find_grid_path((0, 1), &[(2, 1)], grid(&["...", "...", "..."])).unwrap();
+        assert_eq!(cost, 2 * STRAIGHT_STEP_COST);
+
+        // going around the rubble is cheaper than crossing it
+        let (path, cost) = find_grid_path((0, 1), &[(2, 1)], grid(&["...", ".r.", "..."])).unwrap();
+        assert!(!path.contains(&(1, 1)));
+        assert_eq!(cost, 2 * DIAGONAL_STEP_COST);
+
+        let (path, cost) = find_grid_path((0, 0), &[(2, 0)], grid(&[".r."])).unwrap();
+        assert_eq!(path, vec![(0, 0), (1, 0), (2, 0)]);
+        assert_eq!(cost, 3 * STRAIGHT_STEP_COST);
+    }
+
+    #[test]
+    fn returns_none_for_an_unreachable_destination() {
+        assert_eq!(
+            find_grid_path((0, 0), &[(2, 0)], grid(&[".#.", ".#.", ".#."])),
+            None
+        );
+        // the only way would cut the corners of both walls
+        assert_eq!(find_grid_path((0, 0), &[(1, 1)], grid(&[".#", "#."])), None);
+        assert_eq!(find_grid_path((0, 0), &[(1, 0)], grid(&[".#"])), None);
+    }
+}
@@ -0,0 +1,345 @@
+use entities::Tile;
+use util;
+
+/// The asset of every `Tile`, that is hidden from the player
+pub const CONCEALED: &str = "concealed";
+
+/// This is a Map referencing from a 3x3 Tile matrix to a String.
+/// The 3x3 describes a pattern (for example 9 times a `Tile::Wall`) that can occur in the cave's layout and maps to the asset's name that is representing it:
+///
+/// example:
+/// ```
+/// (
+///  [[Ground, Ground, Ground],
+///   [Wall,   Wall,   Wall],
+///   [Wall,   Wall,   Wall]]
+///  , "straight_wall"
+/// ```
+///
+pub type TilePatternMap = Vec<([[Tile; 3]; 3], String)>;
+
+/// The 3x3 surrounding of a `Tile`. The `Tile` itself is in the middle.
+pub type Neighborhood = [[Tile; 3]; 3];
+
+/// Collects the `Neighborhood` of the position (x, y). `tile_at` returns the `Tile` at a position or `None` if the position is out of bounds.
+///
+/// Out of bounds positions are filled with `Tile::default()`.
+pub fn neighborhood_of<F: Fn(i32, i32) -> Option<Tile>>(
+    x: i32,
+    y: i32,
+    tile_at: F,
+) -> Neighborhood {
+    let mut neighborhood = [[Tile::default(); 3]; 3];
+    for delta_x in 0..3 {
+        for delta_y in 0..3 {
+            if let Some(tile) = tile_at(x + delta_x as i32 - 1, y + delta_y as i32 - 1) {
+                neighborhood[delta_x][delta_y] = tile;
+            }
+        }
+    }
+    neighborhood
+}
+
+/// Returns the `Neighborhood` the patterns are matched against: the middle `Tile` stays as it is, the others are replaced by `Tile::as_neighbor`.
+fn pattern_key(neighborhood: &Neighborhood) -> Neighborhood {
+    let mut key = *neighborhood;
+    for x in 0..3 {
+        for y in 0..3 {
+            if (x, y) != (1, 1) {
+                key[x][y] = neighborhood[x][y].as_neighbor();
+            }
+        }
+    }
+    key
+}
+
+/// Searches the `TilePatternMap` for the asset, that fits the middle `Tile` of the `Neighborhood`.
+/// Returns the asset's name and its rotation around the y axis or `None`, if no pattern matches in any of the four rotations.
+pub fn find_sprite<'a>(
+    neighborhood: &Neighborhood,
+    tile_patterns: &'a TilePatternMap,
+) -> Option<(&'a str, f32)> {
+    if let Tile::Ground { concealed: true } = neighborhood[1][1] {
+        return Some((CONCEALED, 0.));
+    };
+
+    let mut key = pattern_key(neighborhood);
+    for rotation in 0..4 {
+        for (dict_key, value) in tile_patterns {
+            let mut pattern_match = true;
+            let dict_key = dict_key.iter().flatten();
+            let key = key.iter().flatten();
+
+            for (dict_tile, key_tile) in dict_key.zip(key) {
+                if !dict_tile.pattern_eq(key_tile) {
+                    pattern_match = false;
+                    break;
+                }
+            }
+            if pattern_match {
+                return Some((
+                    value.as_str(),
+                    (rotation as f32) * std::f32::consts::PI / 2.,
+                ));
+            }
+        }
+        key = util::rotate_3x3(&key);
+    }
+    None
+}
+
+/// The result of `check_coverage`.
+#[derive(Debug, Default)]
+pub struct PatternCoverage {
+    /// Every `Neighborhood`, that no pattern matches in any rotation
+    pub uncovered: Vec<Neighborhood>,
+    /// Every `Neighborhood`, that is matched by patterns of more than one asset, together with the names of those assets
+    pub ambiguous: Vec<(Neighborhood, Vec<String>)>,
+}
+
+impl PatternCoverage {
+    pub fn is_complete(&self) -> bool {
+        self.uncovered.is_empty() && self.ambiguous.is_empty()
+    }
+}
+
+/// Tests every possible `Neighborhood` against the `TilePatternMap`.
+///
+/// A `Neighborhood` is built from `Tile::pattern_classes`, so every combination of `Tile`s, that `Tile::pattern_eq` can tell apart, is checked exactly once.
+/// Neighbors, that look like another class to their neighbors (see `Tile::as_neighbor`), are skipped.
+pub fn check_coverage(tile_patterns: &TilePatternMap) -> PatternCoverage {
+    let classes = Tile::pattern_classes();
+    let neighbor_classes: Vec<Tile> = classes
+        .iter()
+        .filter(|class| class.as_neighbor() == **class)
+        .cloned()
+        .collect();
+    let mut coverage = PatternCoverage::default();
+
+    for center in classes.iter() {
+        // every neighbor is one of the classes, so this counts through all of them like digits of a number
+        let mut digits = [0usize; 8];
+        loop {
+            let mut neighborhood = [[*center; 3]; 3];
+            let mut digit = 0;
+            for x in 0..3 {
+                for y in 0..3 {
+                    if (x, y) != (1, 1) {
+                        neighborhood[x][y] = neighbor_classes[digits[digit]];
+                        digit += 1;
+                    }
+                }
+            }
+
+            let assets = matching_assets(&neighborhood, tile_patterns);
+            if assets.is_empty() {
+                coverage.uncovered.push(neighborhood);
+            } else if assets.len() > 1 {
+                coverage.ambiguous.push((neighborhood, assets));
+            }
+
+            if !increment(&mut digits, neighbor_classes.len()) {
+                break;
+            }
+        }
+    }
+    coverage
+}
+
+/// Returns the names of all assets, whose pattern matches the `Neighborhood` in any rotation.
+fn matching_assets(neighborhood: &Neighborhood, tile_patterns: &TilePatternMap) -> Vec<String> {
+    let mut result: Vec<String> = vec![];
+    let mut key = pattern_key(neighborhood);
+    for _ in 0..4 {
+        for (dict_key, value) in tile_patterns {
+            let pattern_match = dict_key
+                .iter()
+                .flatten()
+                .zip(key.iter().flatten())
+                .all(|(dict_tile, key_tile)| dict_tile.pattern_eq(key_tile));
+            if pattern_match && !result.contains(value) {
+                result.push(value.clone());
+            }
+        }
+        key = util::rotate_3x3(&key);
+    }
+    result
+}
+
+/// Counts `digits` up by one in base `base`. Returns `false` on overflow, which means every combination was visited.
+fn increment(digits: &mut [usize], base: usize) -> bool {
+    for digit in digits.iter_mut() {
+        *digit += 1;
+        if *digit < base {
+            return true;
+        }
+        *digit = 0;
+    }
+    false
+}
+
+/// A `Resource`, that holds the `TilePatternMap` compiled into a table, so that the asset of a `Tile` is looked up instead of searched.
+///
+/// For the patterns, a neighbor is either floor (open ground, water or lava) or something that looks like a wall (a wall, concealed ground or outside of the grid).
+/// So the eight neighbors of a `Tile` fit into a bitmask, which is used as index into the table. See `TileLookupTable::mask_of`.
+#[derive(Default)]
+pub struct TileLookupTable {
+    /// For every center `Tile` of `Tile::pattern_classes` the asset name and rotation of all 256 possible neighbor masks
+    entries: Vec<(Tile, Vec<Option<(String, f32)>>)>,
+}
+
+impl TileLookupTable {
+    /// Runs `find_sprite` once for every possible `Neighborhood` and stores the results.
+    pub fn new(tile_patterns: &TilePatternMap) -> TileLookupTable {
+        let entries = Tile::pattern_classes()
+            .into_iter()
+            .map(|center| {
+                let sprites = (0..=255u8)
+                    .map(|mask| {
+                        find_sprite(
+                            &TileLookupTable::neighborhood_of_mask(center, mask),
+                            tile_patterns,
+                        )
+                        .map(|(asset, rotation)| (asset.to_string(), rotation))
+                    })
+                    .collect();
+                (center, sprites)
+            })
+            .collect();
+
+        TileLookupTable { entries }
+    }
+
+    /// Returns the same as `find_sprite` would for the given `Neighborhood`.
+    pub fn lookup(&self, neighborhood: &Neighborhood) -> Option<(&str, f32)> {
+        let center = neighborhood[1][1];
+        if let Tile::Ground { concealed: true } = center {
+            return Some((CONCEALED, 0.));
+        };
+
+        let mask = TileLookupTable::mask_of(neighborhood) as usize;
+        self.entries
+            .iter()
+            .find(|(class, _)| class.pattern_eq(&center))
+            .and_then(|(_, sprites)| sprites[mask].as_ref())
+            .map(|(asset, rotation)| (asset.as_str(), *rotation))
+    }
+
+    /// The bits of the mask are the neighbors in row-major order, skipping the center. A bit is set if the neighbor is floor.
+    pub fn mask_of(neighborhood: &Neighborhood) -> u8 {
+        let open = Tile::Ground { concealed: false };
+        let mut mask = 0;
+        let mut bit = 0;
+        for x in 0..3 {
+            for y in 0..3 {
+                if (x, y) == (1, 1) {
+                    continue;
+                }
+                if open.pattern_eq(&neighborhood[x][y].as_neighbor()) {
+                    mask |= 1 << bit;
+                }
+                bit += 1;
+            }
+        }
+        mask
+    }
+
+    /// The inverse of `mask_of`.
+    fn neighborhood_of_mask(center: Tile, mask: u8) -> Neighborhood {
+        let mut neighborhood = [[Tile::default(); 3]; 3];
+        neighborhood[1][1] = center;
+        let mut bit = 0;
+        for x in 0..3 {
+            for y in 0..3 {
+                if (x, y) == (1, 1) {
+                    continue;
+                }
+                if mask & (1 << bit) != 0 {
+                    neighborhood[x][y] = Tile::Ground { concealed: false };
+                }
+                bit += 1;
+            }
+        }
+        neighborhood
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use amethyst::config::Config;
+    use entities::WallMaterial;
+
+    fn load_tile_patterns() -> TilePatternMap {
+        TilePatternMap::load_no_fallback(format!(
+            "{}/resources/tile_config.ron",
+            env!("CARGO_MANIFEST_DIR")
+        ))
+        .unwrap()
+    }
+
+    #[test]
+    fn tile_config_covers_every_neighborhood() {
+        let coverage = check_coverage(&load_tile_patterns());
+        assert!(
+            coverage.uncovered.is_empty(),
+            "no pattern matches {:?}",
+            coverage.uncovered
+        );
+        assert!(
+            coverage.ambiguous.is_empty(),
+            "several assets match {:?}",
+            coverage.ambiguous
+        );
+    }
+
+    #[test]
+    fn lookup_table_agrees_with_find_sprite() {
+        let tile_patterns = load_tile_patterns();
+        let lookup_table = TileLookupTable::new(&tile_patterns);
+        // the mask cannot tell these apart, so the neighbors are taken from all of them
+        let floors = [
+            Tile::Ground { concealed: false },
+            Tile::Water,
+            Tile::Lava,
+            Tile::Rubble { level: 2 },
+        ];
+        let walls = [
+            Tile::default(),
+            Tile::Ground { concealed: true },
+            Tile::Wall {
+                material: WallMaterial::Dirt,
+                ore: 3,
+            },
+        ];
+
+        for center in Tile::pattern_classes() {
+            for mask in 0..=255u8 {
+                let mut neighborhood = [[center; 3]; 3];
+                let mut bit = 0;
+                for x in 0..3 {
+                    for y in 0..3 {
+                        if (x, y) == (1, 1) {
+                            continue;
+                        }
+                        let variant = mask as usize + bit;
+                        neighborhood[x][y] = if mask & (1 << bit) != 0 {
+                            floors[variant % floors.len()]
+                        } else {
+                            walls[variant % walls.len()]
+                        };
+                        bit += 1;
+                    }
+                }
+
+                assert_eq!(TileLookupTable::mask_of(&neighborhood), mask);
+                assert_eq!(
+                    lookup_table.lookup(&neighborhood),
+                    find_sprite(&neighborhood, &tile_patterns),
+                    "sprite selection differs for {:?}",
+                    neighborhood
+                );
+            }
+        }
+    }
+}
@@ -0,0 +1,247 @@
+use entities::{
+    buildings::{check_start_footprint, BuildingType, Facing, PlacementError},
+    Tile,
+};
+use level::{find_sprite, neighborhood_of, LevelDocument, Neighborhood, TilePatternMap, CONCEALED};
+
+use std::{
+    collections::{BTreeSet, VecDeque},
+    fmt,
+    path::{Path, PathBuf},
+};
+
+/// Everything that can be wrong with a level file and would otherwise only show up while playing it.
+#[derive(Debug)]
+pub enum LevelProblem {
+    /// The grid does not contain a single `Tile`
+    EmptyGrid,
+    /// The column at `x` has another length than the first one
+    RaggedGrid {
+        x: usize,
+        expected: usize,
+        found: usize,
+    },
+    /// No pattern of the `TilePatternMap` matches the `Tile` at this position.
+    /// `revealed` indicates, whether this happens right at the start or only after the whole cave has been revealed.
+    NoPattern {
+        x: usize,
+        y: usize,
+        revealed: bool,
+        neighborhood: Neighborhood,
+    },
+    /// The `Base` is placed outside of the grid
+    BaseOutOfBounds { x: i32, y: i32 },
+    /// The `Base` is not placed on `Tile::Ground`
+    BaseNotOnGround { x: i32, y: i32, tile: Tile },
+    /// The entrance of the `Base` at this position cannot be walked on
+    BaseEntranceBlocked { x: i32, y: i32 },
+    /// A starting `RockRaider` is placed on a position that is not ground
+    RaiderNotOnGround { x: i32, y: i32 },
+    /// A starting `RockRaider` is placed on concealed ground, that is not revealed together with the `Base`, so it cannot move
+    RaiderConcealed { x: i32, y: i32 },
+    /// A mesh or texture, that is used by the level, does not exist
+    MissingAsset { path: PathBuf, x: usize, y: usize },
+}
+
+impl fmt::Display for LevelProblem {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            LevelProblem::EmptyGrid => write!(f, "the grid is empty"),
+            LevelProblem::RaggedGrid { x, expected, found } => write!(
+                f,
+                "({}, _): column has {} tiles, but the first column has {}",
+                x, found, expected
+            ),
+            LevelProblem::NoPattern {
+                x,
+                y,
+                revealed,
+                neighborhood,
+            } => write!(
+                f,
+                "({}, {}): no tile pattern matches {}: {:?}",
+                x,
+                y,
+                if *revealed {
+                    "once the cave is revealed"
+                } else {
+                    "at level start"
+                },
+                neighborhood
+            ),
+            LevelProblem::BaseOutOfBounds { x, y } => {
+                write!(f, "({}, {}): the base is outside of the grid", x, y)
+            }
+            LevelProblem::BaseNotOnGround { x, y, tile } => {
+                write!(f, "({}, {}): the base cannot be built on {:?}", x, y, tile)
+            }
+            LevelProblem::BaseEntranceBlocked { x, y } => {
+                write!(f, "({}, {}): the entrance of the base is blocked", x, y)
+            }
+            LevelProblem::RaiderNotOnGround { x, y } => {
+                write!(
+                    f,
+                    "({}, {}): a rock raider starts outside of the cave",
+                    x, y
+                )
+            }
+            LevelProblem::RaiderConcealed { x, y } => write!(
+                f,
+                "({}, {}): a rock raider starts on ground, that is never revealed",
+                x, y
+            ),
+            LevelProblem::MissingAsset { path, x, y } => {
+                write!(f, "({}, {}): asset {} does not exist", x, y, path.display())
+            }
+        }
+    }
+}
+
+/// Checks the level for every problem listed in `LevelProblem`. Assets are searched relative to `assets_dir`.
+///
+/// Returns an empty `Vec` if the level is fine.
+pub fn validate_level(
+    document: &LevelDocument,
+    tile_patterns: &TilePatternMap,
+    assets_dir: &Path,
+) -> Vec<LevelProblem> {
+    let grid = &document.grid;
+    let mut problems = vec![];
+
+    if grid.is_empty() || grid[0].is_empty() {
+        problems.push(LevelProblem::EmptyGrid);
+        return problems;
+    }
+
+    let expected = grid[0].len();
+    for (x, column) in grid.iter().enumerate() {
+        if column.len() != expected {
+            problems.push(LevelProblem::RaggedGrid {
+                x,
+                expected,
+                found: column.len(),
+            });
+        }
+    }
+
+    // the meshes change whenever ground is revealed, so both the initial and the completely revealed cave have to be covered by the patterns
+    let revealed_grid: Vec<Vec<Tile>> = grid
+        .iter()
+        .map(|column| {
+            column
+                .iter()
+                .map(|tile| {
+                    let mut tile = *tile;
+                    tile.reveal();
+                    tile
+                })
+                .collect()
+        })
+        .collect();
+
+    let mut used_assets = BTreeSet::new();
+    for (grid, revealed) in [(grid, false), (&revealed_grid, true)].iter() {
+        for (x, column) in grid.iter().enumerate() {
+            for y in 0..column.len() {
+                let neighborhood = neighborhood_of(x as i32, y as i32, |x, y| tile_at(grid, x, y));
+                match find_sprite(&neighborhood, tile_patterns) {
+                    Some((asset, _)) => {
+                        // see `LevelGrid::update_tile`
+                        let texture = match neighborhood[1][1] {
+                            Tile::Wall { material, .. } if asset != CONCEALED => {
+                                material.texture_name()
+                            }
+                            _ => asset,
+                        };
+                        used_assets.insert((asset.to_string(), texture.to_string(), x, y));
+                    }
+                    None => problems.push(LevelProblem::NoPattern {
+                        x,
+                        y,
+                        revealed: *revealed,
+                        neighborhood,
+                    }),
+                }
+            }
+        }
+    }
+
+    // report every missing asset only once, at the first position it is used
+    let mut reported = BTreeSet::new();
+    for (mesh, texture, x, y) in used_assets {
+        for path in [
+            assets_dir.join(format!("meshes/{}.obj", mesh)),
+            assets_dir.join(format!("textures/{}.png", texture)),
+        ]
+        .iter()
+        {
+            if !path.is_file() && reported.insert(path.clone()) {
+                problems.push(LevelProblem::MissingAsset {
+                    path: path.clone(),
+                    x,
+                    y,
+                });
+            }
+        }
+    }
+
+    // the same check `LevelState` runs before it builds the `Base`
+    let (x, y) = document.info.base_position;
+    let facing = Facing::default();
+    let base = check_start_footprint(BuildingType::Base, x, y, facing, |x, y| tile_at(grid, x, y));
+    match base {
+        Ok(()) => (),
+        Err(PlacementError::NotGround(tile)) => {
+            problems.push(LevelProblem::BaseNotOnGround { x, y, tile })
+        }
+        Err(PlacementError::BlockedEntrance) => {
+            let (x, y) = facing.in_front_of(x, y);
+            problems.push(LevelProblem::BaseEntranceBlocked { x, y })
+        }
+        Err(_) => problems.push(LevelProblem::BaseOutOfBounds { x, y }),
+    }
+
+    let revealed_by_base = revealed_by_base(grid, document.info.base_position);
+    for &(x, y) in document.info.raiders.iter() {
+        match tile_at(grid, x, y) {
+            Some(Tile::Ground { concealed: false }) => (),
+            Some(Tile::Ground { concealed: true }) => {
+                if !revealed_by_base.contains(&(x, y)) {
+                    problems.push(LevelProblem::RaiderConcealed { x, y });
+                }
+            }
+            _ => problems.push(LevelProblem::RaiderNotOnGround { x, y }),
+        }
+    }
+
+    problems
+}
+
+/// Returns the positions, that the `GroundRevealSystem` reveals, when the level starts. These are the `Base`'s position and all concealed ground connected to it.
+fn revealed_by_base(grid: &Vec<Vec<Tile>>, base_position: (i32, i32)) -> BTreeSet<(i32, i32)> {
+    let mut revealed = BTreeSet::new();
+    let mut queue = VecDeque::new();
+    revealed.insert(base_position);
+    queue.push_back(base_position);
+
+    while let Some((x, y)) = queue.pop_front() {
+        for &(d_x, d_y) in [(0, -1), (1, 0), (0, 1), (-1, 0)].iter() {
+            let neighbor = (x + d_x, y + d_y);
+            if let Some(Tile::Ground { concealed: true }) = tile_at(grid, neighbor.0, neighbor.1) {
+                if revealed.insert(neighbor) {
+                    queue.push_back(neighbor);
+                }
+            }
+        }
+    }
+    revealed
+}
+
+fn tile_at(grid: &Vec<Vec<Tile>>, x: i32, y: i32) -> Option<Tile> {
+    if x < 0 || y < 0 {
+        return None;
+    }
+    grid.get(x as usize)
+        .and_then(|column| column.get(y as usize))
+        .cloned()
+}
@@ -0,0 +1,33 @@
+extern crate amethyst;
+#[macro_use]
+extern crate amethyst_derive;
+#[macro_use]
+extern crate log;
+extern crate serde;
+#[macro_use]
+extern crate serde_derive;
+extern crate ncollide3d;
+extern crate pathfinding;
+extern crate rand;
+
+pub mod assetmanagement;
+pub mod entities;
+pub mod eventhandling;
+pub mod level;
+pub mod main_state;
+pub mod systems;
+pub mod util;
+
+/// The scene that is currently shown. Systems are paused depending on this `Resource`.
+#[derive(PartialEq)]
+pub enum GameScene {
+    Main,
+    Level,
+    None,
+}
+
+impl Default for GameScene {
+    fn default() -> Self {
+        GameScene::None
+    }
+}
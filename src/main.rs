@@ -1,22 +1,5 @@
 extern crate amethyst;
-#[macro_use]
-extern crate amethyst_derive;
-#[macro_use]
-extern crate log;
-extern crate serde;
-#[macro_use]
-extern crate serde_derive;
-extern crate ncollide3d;
-extern crate pathfinding;
-extern crate rand;
-
-mod assetmanagement;
-mod entities;
-mod eventhandling;
-mod level;
-mod main_state;
-mod systems;
-mod util;
+extern crate rock_raiders;
 
 use amethyst::{
     core::transform::TransformBundle,
@@ -26,11 +9,14 @@ use amethyst::{
     ui::{DrawUi, UiBundle},
 };
 
-use eventhandling::{GameEvent, GameEventReader};
+use rock_raiders::{
+    eventhandling::{self, GameEvent, GameEventReader},
+    main_state::MainState,
+    systems, GameScene,
+};
 
 fn main() -> amethyst::Result<()> {
     amethyst::start_logger(Default::default());
-    use main_state::MainState;
 
     let path = format!("{}/resources/display.ron", env!("CARGO_MANIFEST_DIR"));
 
@@ -56,10 +42,60 @@ fn main() -> amethyst::Result<()> {
             "mouse_ray_system",
             &[],
         )
+        .with(
+            systems::RepathingSystem::default().pausable(GameScene::Level),
+            "repathing_system",
+            &[],
+        )
         .with(
             systems::MovementSystem.pausable(GameScene::Level),
             "movement_system",
-            &["transform_system"],
+            &["transform_system", "repathing_system"],
+        )
+        .with(
+            systems::DrillingSystem.pausable(GameScene::Level),
+            "drilling_system",
+            &["movement_system"],
+        )
+        .with(
+            systems::ClearingSystem.pausable(GameScene::Level),
+            "clearing_system",
+            &["movement_system"],
+        )
+        .with(
+            systems::OreCollectionSystem.pausable(GameScene::Level),
+            "ore_collection_system",
+            &["movement_system"],
+        )
+        .with(
+            systems::ConstructionSystem.pausable(GameScene::Level),
+            "construction_system",
+            &["movement_system"],
+        )
+        .with(
+            systems::JobSystem.pausable(GameScene::Level),
+            "job_system",
+            &[
+                "drilling_system",
+                "clearing_system",
+                "ore_collection_system",
+                "construction_system",
+            ],
+        )
+        .with(
+            systems::LavaSystem.pausable(GameScene::Level),
+            "lava_system",
+            &["movement_system"],
+        )
+        .with(
+            systems::VitalsSystem::default().pausable(GameScene::Level),
+            "vitals_system",
+            &["lava_system"],
+        )
+        .with(
+            systems::TeleportSystem.pausable(GameScene::Level),
+            "teleport_system",
+            &["movement_system"],
         )
         .with(
             eventhandling::CameraMovementSystem.pausable(GameScene::Level),
@@ -76,27 +112,27 @@ fn main() -> amethyst::Result<()> {
             "oxygen_system",
             &["ui_transform"],
         )
+        .with(
+            systems::SpawnSystem.pausable(GameScene::Level),
+            "spawn_system",
+            &["ui_transform"],
+        )
         .with(
             eventhandling::HoverInteractionSystem.pausable(GameScene::Level),
             "mouse_input_system",
             &["mouse_ray_system"],
+        )
+        .with(
+            systems::PlacementPreviewSystem.pausable(GameScene::Level),
+            "placement_preview_system",
+            &["mouse_input_system"],
         );
 
-    let mut game =
-        CoreApplication::<_, GameEvent, GameEventReader>::new(assets_dir, MainState, game_data)?;
+    let mut game = CoreApplication::<_, GameEvent, GameEventReader>::new(
+        assets_dir,
+        MainState::default(),
+        game_data,
+    )?;
     game.run();
     Ok(())
 }
-
-#[derive(PartialEq)]
-enum GameScene {
-    Main,
-    Level,
-    None,
-}
-
-impl Default for GameScene {
-    fn default() -> Self {
-        GameScene::None
-    }
-}
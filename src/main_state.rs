@@ -1,43 +1,127 @@
 use amethyst::{
+    ecs::Entity,
     input::{is_close_requested, is_key_down},
     prelude::*,
     renderer::VirtualKeyCode,
+    ui::{UiCreator, UiText, UiTransform},
 };
 use eventhandling::GameEvent;
+use util::find_ui_by_name;
 use GameScene;
 
-use level::LevelState;
+use level::{LevelDocument, LevelState};
+
+use std::{fs, path::PathBuf};
 
 /// The main state of the Game. When started, this will be the first state on the stack.
-pub struct MainState;
+///
+/// It lets the player choose one of the levels found in `assets/levels/`.
+#[derive(Default)]
+pub struct MainState {
+    /// Every level that was found on disk, with the name that is displayed to the player
+    levels: Vec<(PathBuf, String)>,
+    /// Index into `levels`
+    selected: usize,
+    /// The root entity of the level selection ui
+    menu: Option<Entity>,
+}
 
 impl MainState {
     fn scene() -> GameScene {
         GameScene::Main
     }
+
+    /// Returns every level file in `assets/levels/`, sorted by its path.
+    fn discover_levels() -> Vec<(PathBuf, String)> {
+        let directory = format!("{}/assets/levels", env!("CARGO_MANIFEST_DIR"));
+        let mut paths: Vec<PathBuf> = match fs::read_dir(&directory) {
+            Ok(entries) => entries
+                .filter_map(|entry| entry.ok().map(|entry| entry.path()))
+                .filter(|path| path.extension().map_or(false, |ext| ext == "ron"))
+                .collect(),
+            Err(error) => {
+                error!("Cannot read level directory {}: {}", directory, error);
+                vec![]
+            }
+        };
+        paths.sort();
+
+        paths
+            .into_iter()
+            .filter_map(|path| match LevelDocument::load_from(&path) {
+                Ok(document) => Some((path, document.info.name)),
+                Err(error) => {
+                    warn!("Skipping level {}: {}", path.display(), error);
+                    None
+                }
+            })
+            .collect()
+    }
+
+    fn show_menu(&mut self, world: &mut World) {
+        self.levels = MainState::discover_levels();
+        self.selected = self.selected.min(self.levels.len().saturating_sub(1));
+        self.menu =
+            Some(world.exec(|mut creator: UiCreator| {
+                creator.create("ui/level_selection/prefab.ron", ())
+            }));
+    }
+
+    fn hide_menu(&mut self, world: &mut World) {
+        if let Some(menu) = self.menu.take() {
+            if let Err(error) = world.delete_entity(menu) {
+                warn!("Cannot delete level selection: {:?}", error);
+            }
+        }
+    }
+
+    /// Writes the name of the selected level into the level selection ui, as soon as the ui is loaded.
+    fn update_menu(&self, world: &World) {
+        let entities = world.entities();
+        let ui_transforms = world.read_storage::<UiTransform>();
+        if let Some(label) = find_ui_by_name("level_selection", &entities, &ui_transforms) {
+            if let Some(text) = world.write_storage::<UiText>().get_mut(label) {
+                text.text = match self.levels.get(self.selected) {
+                    Some((_, name)) => format!("<  {}  >", name),
+                    None => String::from("No levels found in assets/levels"),
+                };
+            }
+        }
+    }
 }
 
 impl<'a, 'b> State<GameData<'a, 'b>, GameEvent> for MainState {
     fn on_start(&mut self, data: StateData<GameData>) {
         *data.world.write_resource() = MainState::scene();
+        self.show_menu(data.world);
     }
 
     fn on_resume(&mut self, data: StateData<GameData>) {
         *data.world.write_resource() = MainState::scene();
+        // the level deletes every entity when it stops, so the menu has to be recreated anyway
+        self.menu = None;
+        self.show_menu(data.world);
     }
 
     fn handle_event(
         &mut self,
-        _: StateData<GameData>,
+        data: StateData<GameData>,
         event: GameEvent,
     ) -> Trans<GameData<'a, 'b>, GameEvent> {
         if let GameEvent::Window(event) = &event {
             if is_close_requested(&event) || is_key_down(&event, VirtualKeyCode::Escape) {
                 return Trans::Quit;
-            } else if is_key_down(&event, VirtualKeyCode::Tab) {
-                return Trans::Push(Box::new(LevelState {
-                    mouse_button_was_down: false,
-                }));
+            } else if is_key_down(&event, VirtualKeyCode::Left) && !self.levels.is_empty() {
+                self.selected = (self.selected + self.levels.len() - 1) % self.levels.len();
+            } else if is_key_down(&event, VirtualKeyCode::Right) && !self.levels.is_empty() {
+                self.selected = (self.selected + 1) % self.levels.len();
+            } else if is_key_down(&event, VirtualKeyCode::Tab)
+                || is_key_down(&event, VirtualKeyCode::Return)
+            {
+                if let Some((path, _)) = self.levels.get(self.selected).cloned() {
+                    self.hide_menu(data.world);
+                    return Trans::Push(Box::new(LevelState::new(path)));
+                }
             }
         }
         Trans::None
@@ -45,6 +129,7 @@ impl<'a, 'b> State<GameData<'a, 'b>, GameEvent> for MainState {
 
     fn update(&mut self, data: StateData<GameData>) -> Trans<GameData<'a, 'b>, GameEvent> {
         data.data.update(&data.world);
+        self.update_menu(&data.world);
         Trans::None
     }
 }
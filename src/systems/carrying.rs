@@ -0,0 +1,77 @@
+use amethyst::{
+    core::{
+        nalgebra::Vector3,
+        transform::{Parent, Transform},
+    },
+    ecs::prelude::{Component, DenseVecStorage, Entity, WriteStorage},
+};
+
+/// The height above the carrier's pivot, at which a carried item is held
+const CARRY_HEIGHT: f32 = 0.4;
+
+/// The slot of a `RockRaider`, that holds the one item it carries, like a piece of `Ore`.
+///
+/// A carried item is a child of its carrier, the same way a `Base` is a child of its `Tile`. So it moves along without any system taking care of it.
+#[derive(Debug, Default)]
+pub struct CarrySlot {
+    item: Option<Entity>,
+}
+
+impl CarrySlot {
+    /// The carried item, if any
+    pub fn item(&self) -> Option<Entity> {
+        self.item
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.item.is_none()
+    }
+
+    /// Attaches the item to the carrier. Returns `false` without changing anything, if the slot holds another item already.
+    pub fn pick_up(
+        &mut self,
+        carrier: Entity,
+        item: Entity,
+        parents: &mut WriteStorage<Parent>,
+        transforms: &mut WriteStorage<Transform>,
+    ) -> bool {
+        if self.item.is_some() {
+            return false;
+        }
+
+        parents.insert(item, Parent { entity: carrier }).unwrap();
+        // as a child, the position is relative to the carrier
+        transforms
+            .get_mut(item)
+            .unwrap()
+            .set_position(Vector3::new(0., CARRY_HEIGHT, 0.));
+        self.item = Some(item);
+        true
+    }
+
+    /// Detaches the carried item and puts it onto the ground at the position. Returns the item, if there was one.
+    pub fn drop_off(
+        &mut self,
+        position: &Vector3<f32>,
+        parents: &mut WriteStorage<Parent>,
+        transforms: &mut WriteStorage<Transform>,
+    ) -> Option<Entity> {
+        let item = self.hand_over(parents)?;
+        transforms
+            .get_mut(item)
+            .unwrap()
+            .set_position(Vector3::new(position.x, 0., position.z));
+        Some(item)
+    }
+
+    /// Detaches the carried item without putting it anywhere. This is used, when the item is used up, like `Ore` that is delivered to the `Base`.
+    pub fn hand_over(&mut self, parents: &mut WriteStorage<Parent>) -> Option<Entity> {
+        let item = self.item.take()?;
+        parents.remove(item);
+        Some(item)
+    }
+}
+
+impl Component for CarrySlot {
+    type Storage = DenseVecStorage<Self>;
+}
@@ -0,0 +1,127 @@
+use amethyst::{
+    core::{timing::Time, transform::Transform},
+    ecs::prelude::{
+        Component, DenseVecStorage, Entities, Entity, Join, Read, ReadStorage, System, Write,
+        WriteStorage,
+    },
+    shrev::EventChannel,
+};
+
+use assetmanagement::util::AssetStorages;
+use entities::Tile;
+use eventhandling::{ClickHandlerComponent, HoverHandlerComponent, Hovered};
+use level::{LevelGrid, TileChanged, TileLookupTable};
+use systems::Path;
+
+/// The seconds it takes to remove one level of rubble
+const CLEAR_TIME: f32 = 1.5;
+
+///
+/// This system lets `RockRaider`s clear the rubble they were ordered to, as soon as they stand on it.
+/// They keep clearing until the rubble is gone. See `Tile::clear`
+///
+pub struct ClearingSystem;
+
+impl<'a> System<'a> for ClearingSystem {
+    type SystemData = (
+        Entities<'a>,
+        Read<'a, Time>,
+        ReadStorage<'a, Path>,
+        WriteStorage<'a, Clear>,
+        WriteStorage<'a, Tile>,
+        WriteStorage<'a, Transform>,
+        Write<'a, EventChannel<TileChanged>>,
+        Read<'a, LevelGrid>,
+        Read<'a, TileLookupTable>,
+        Write<'a, Hovered>,
+        WriteStorage<'a, HoverHandlerComponent>,
+        WriteStorage<'a, ClickHandlerComponent>,
+        AssetStorages<'a>,
+    );
+
+    fn run(
+        &mut self,
+        (
+            entities,
+            time,
+            paths,
+            mut clears,
+            mut tiles,
+            mut transforms,
+            mut tile_changes,
+            level_grid,
+            dict,
+            mut hovered,
+            mut hovers,
+            mut clickers,
+            mut storages,
+        ): Self::SystemData,
+    ) {
+        let mut finished: Vec<(Entity, Entity)> = vec![];
+
+        // only entities without a `Path` have arrived at the rubble
+        for (entity, clear, _) in (&entities, &mut clears, !&paths).join() {
+            clear.remaining -= time.delta_seconds();
+            if clear.remaining <= 0. {
+                clear.remaining = CLEAR_TIME;
+                finished.push((entity, clear.rubble));
+            }
+        }
+
+        for (entity, rubble) in finished {
+            let tile = tiles.get_mut(rubble).unwrap();
+            let before = *tile;
+            tile.clear();
+            let changed = *tile != before;
+
+            // stop as soon as the rubble is gone, even if another `RockRaider` cleared it
+            let gone = match tile {
+                Tile::Rubble { .. } => false,
+                _ => true,
+            };
+            if gone {
+                clears.remove(entity);
+            }
+            // another `RockRaider` cleared the last level already
+            if !changed {
+                continue;
+            }
+            tile_changes.single_write(TileChanged { tile: rubble });
+
+            let (x, y) = LevelGrid::grid_position(transforms.get(rubble).unwrap().translation());
+            level_grid.update_tile(
+                x,
+                y,
+                &dict,
+                &mut transforms,
+                &tiles,
+                &mut storages,
+                &mut hovered,
+                &mut hovers,
+                &mut clickers,
+            );
+        }
+    }
+}
+
+/// The order to clear rubble. The `RockRaider` starts clearing as soon as it has no `Path` anymore.
+#[derive(Debug)]
+pub struct Clear {
+    /// The entity of the `Tile::Rubble` to clear
+    pub rubble: Entity,
+    /// The seconds left until the next level of rubble is removed
+    pub remaining: f32,
+}
+
+impl Clear {
+    pub fn new(rubble: Entity) -> Self {
+        Clear {
+            rubble,
+            remaining: CLEAR_TIME,
+        }
+    }
+}
+
+impl Component for Clear {
+    type Storage = DenseVecStorage<Self>;
+}
@@ -0,0 +1,247 @@
+use amethyst::{
+    core::{nalgebra::Point2, timing::Time, transform::Parent},
+    ecs::prelude::{
+        Component, DenseVecStorage, Entities, Entity, Join, Read, ReadStorage, System, World,
+        Write, WriteStorage,
+    },
+};
+
+use entities::{
+    buildings::{Base, ConstructionSite},
+    Ore, OreStorages, Tile,
+};
+use level::{FlowFields, LevelGrid};
+use systems::{CarrySlot, FlowDestination, JobBoard, JobKind, Path};
+
+///
+/// This system lets `RockRaider`s build the `ConstructionSite`s, that the player placed.
+///
+/// As long as a site is not finished, a `JobKind::Build` job is posted for it. Its `RockRaider` fetches the site's reserved ore piece by piece from the `Base`.
+/// The ore was already taken from the `OreStockpile`, when the site was placed. See `place_building`
+/// Ore, that does not reach the site, is given back to the site's reserved ore. See `Construct::cancel`
+/// Once all ore is delivered, every `RockRaider` at the site works on it, until no work is left. The finished site is turned into its building by the `LevelState`.
+///
+pub struct ConstructionSystem;
+
+impl<'a> System<'a> for ConstructionSystem {
+    type SystemData = (
+        Entities<'a>,
+        Read<'a, Time>,
+        Read<'a, LevelGrid>,
+        Write<'a, FlowFields>,
+        Write<'a, JobBoard>,
+        ReadStorage<'a, Tile>,
+        ReadStorage<'a, Base>,
+        ReadStorage<'a, Path>,
+        WriteStorage<'a, Parent>,
+        WriteStorage<'a, ConstructionSite>,
+        WriteStorage<'a, FlowDestination>,
+        WriteStorage<'a, Construct>,
+        WriteStorage<'a, CarrySlot>,
+        OreStorages<'a>,
+    );
+
+    fn run(
+        &mut self,
+        (
+            entities,
+            time,
+            level_grid,
+            mut flow_fields,
+            mut job_board,
+            tiles,
+            bases,
+            paths,
+            mut parents,
+            mut sites,
+            mut flow_destinations,
+            mut orders,
+            mut carry_slots,
+            mut ore_storages,
+        ): Self::SystemData,
+    ) {
+        // the grid position of the `Tile` the `Base` is built on
+        let base_position = (&bases, &parents)
+            .join()
+            .map(|(_, parent)| {
+                LevelGrid::grid_position(ore_storages.1.get(parent.entity).unwrap().translation())
+            })
+            .next();
+
+        for (site, parent) in (&sites, &parents).join() {
+            if !site.is_finished() {
+                job_board.post(JobKind::Build, parent.entity);
+            }
+        }
+
+        // only entities without a `Path` or `FlowDestination` have arrived
+        let arrived: Vec<(Entity, Construct)> = (&entities, &orders, !&paths, !&flow_destinations)
+            .join()
+            .map(|(entity, order, _, _)| (entity, *order))
+            .collect();
+
+        for (entity, order) in arrived {
+            let site_tile = order.site();
+            let site_entity = (&entities, &sites, &parents)
+                .join()
+                .find(|(_, _, parent)| parent.entity == site_tile)
+                .map(|(site_entity, _, _)| site_entity);
+            let position = *ore_storages.1.get(entity).unwrap().translation();
+            let (x, y) = LevelGrid::grid_position(&position);
+            let next_to =
+                |(target_x, target_y): (i32, i32)| (target_x - x).abs() + (target_y - y).abs() <= 1;
+
+            let carry_slot = carry_slots.get_mut(entity).unwrap();
+            let arrived_at_site = site_entity
+                .and_then(|site_entity| sites.get(site_entity))
+                .map_or(false, |site| {
+                    next_to(site.position) || order == Construct::ToBase(site_tile)
+                });
+            // the site is finished, or the `RockRaider` was sent somewhere else in the meantime
+            if !arrived_at_site {
+                orders.remove(entity);
+                if let Some(ore) = carry_slot.hand_over(&mut parents) {
+                    give_back(ore, site_entity, &entities, &mut sites);
+                }
+                continue;
+            }
+            let site = sites.get_mut(site_entity.unwrap()).unwrap();
+
+            match order {
+                Construct::ToSite(_) => {
+                    if let Some(ore) = carry_slot.hand_over(&mut parents) {
+                        entities.delete(ore).unwrap();
+                        site.delivered_ore += 1;
+                        debug!(
+                            "Ore delivered to the construction site. Missing: {}",
+                            site.missing_ore()
+                        );
+                    }
+
+                    let missing_ore = site.missing_ore();
+                    if missing_ore == 0 {
+                        site.remaining_work -= time.delta_seconds();
+                        // the `RockRaider` stays at the site, until the work is done
+                        if site.is_finished() {
+                            orders.remove(entity);
+                        }
+                        continue;
+                    }
+
+                    // others bring the rest of the ore
+                    if site.reserved_ore == 0 {
+                        orders.remove(entity);
+                        continue;
+                    }
+                    let destination = base_position
+                        .map(|(base_x, base_y)| FlowDestination::next_to(base_x, base_y))
+                        .filter(|destination| {
+                            flow_fields
+                                .get(&destination.tiles, &level_grid, &tiles)
+                                .cost(x, y)
+                                .is_some()
+                        });
+                    match destination {
+                        Some(destination) => {
+                            flow_destinations.insert(entity, destination).unwrap();
+                            orders.insert(entity, Construct::ToBase(site_tile)).unwrap();
+                        }
+                        None => {
+                            orders.remove(entity);
+                        }
+                    }
+                }
+                Construct::ToBase(_) => {
+                    let at_base = base_position.map_or(false, |base| next_to(base));
+                    let destination = FlowDestination::next_to(site.position.0, site.position.1);
+                    let reachable = flow_fields
+                        .get(&destination.tiles, &level_grid, &tiles)
+                        .cost(x, y)
+                        .is_some();
+                    if !at_base || !reachable || site.reserved_ore == 0 {
+                        orders.remove(entity);
+                        continue;
+                    }
+
+                    // a piece of the reserved ore is carried to the site, it does not lie around as `Ore`
+                    site.reserved_ore -= 1;
+                    let ore = Ore::instantiate(
+                        &entities,
+                        Point2::new(position.x, position.z),
+                        &mut ore_storages,
+                    );
+                    // carried ore does not lie around anymore
+                    ore_storages.0.remove(ore);
+                    carry_slot.pick_up(entity, ore, &mut parents, &mut ore_storages.1);
+                    flow_destinations.insert(entity, destination).unwrap();
+                    orders.insert(entity, Construct::ToSite(site_tile)).unwrap();
+                }
+            }
+        }
+    }
+}
+
+/// The order to bring ore to a `ConstructionSite` and to work on it. Both variants hold the `Tile` the site stands on.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Construct {
+    /// The `RockRaider` walks to the site, with or without ore, and works on it, once all ore is there
+    ToSite(Entity),
+    /// The `RockRaider` walks to the `Base` to fetch a piece of the ore, that was reserved for the site
+    ToBase(Entity),
+}
+
+impl Construct {
+    /// The `Tile` the `ConstructionSite` stands on
+    pub fn site(self) -> Entity {
+        match self {
+            Construct::ToSite(site) | Construct::ToBase(site) => site,
+        }
+    }
+
+    /// Cancels the order of the `RockRaider`, if it has one. The ore it carries to the site is given back to the ore reserved for the site.
+    pub fn cancel(rock_raider: Entity, world: &World) {
+        let order = match world.write_storage::<Construct>().remove(rock_raider) {
+            Some(order) => order,
+            None => return,
+        };
+
+        let ore = world
+            .write_storage::<CarrySlot>()
+            .get_mut(rock_raider)
+            .and_then(|carry_slot| carry_slot.hand_over(&mut world.write_storage::<Parent>()));
+        if let Some(ore) = ore {
+            let site_entity = (
+                &world.entities(),
+                &world.read_storage::<ConstructionSite>(),
+                &world.read_storage::<Parent>(),
+            )
+                .join()
+                .find(|(_, _, parent)| parent.entity == order.site())
+                .map(|(site_entity, _, _)| site_entity);
+            give_back(
+                ore,
+                site_entity,
+                &world.entities(),
+                &mut world.write_storage::<ConstructionSite>(),
+            );
+        }
+    }
+}
+
+/// Deletes a piece of ore, that was carried to a `ConstructionSite`, and adds it to the site's reserved ore again, so that it is fetched from the `Base` once more.
+/// Reserved ore never lies around as `Ore`, otherwise it could be delivered to the `OreStockpile` a second time.
+fn give_back(
+    ore: Entity,
+    site_entity: Option<Entity>,
+    entities: &Entities,
+    sites: &mut WriteStorage<ConstructionSite>,
+) {
+    entities.delete(ore).unwrap();
+    if let Some(site) = site_entity.and_then(|site_entity| sites.get_mut(site_entity)) {
+        site.reserved_ore += 1;
+    }
+}
+
+impl Component for Construct {
+    type Storage = DenseVecStorage<Self>;
+}
@@ -0,0 +1,99 @@
+use amethyst::{
+    core::timing::Time,
+    ecs::prelude::{
+        Component, DenseVecStorage, Entities, Entity, Join, Read, ReadStorage, System, Write,
+        WriteStorage,
+    },
+    shrev::EventChannel,
+};
+
+use entities::{OreStorages, Tile};
+use level::{LevelGrid, TileChanged};
+use systems::{Path, RevealQueue};
+
+///
+/// This system lets `RockRaider`s drill the wall they were ordered to, as soon as they arrived next to it.
+/// A drilled wall turns into revealed ground and drops the `Ore` it contained. See `LevelGrid::drill_wall`
+///
+pub struct DrillingSystem;
+
+impl<'a> System<'a> for DrillingSystem {
+    type SystemData = (
+        Entities<'a>,
+        Read<'a, Time>,
+        ReadStorage<'a, Path>,
+        WriteStorage<'a, Drill>,
+        WriteStorage<'a, Tile>,
+        Write<'a, Option<RevealQueue>>,
+        Write<'a, EventChannel<TileChanged>>,
+        Read<'a, LevelGrid>,
+        OreStorages<'a>,
+    );
+
+    fn run(
+        &mut self,
+        (
+            entities,
+            time,
+            paths,
+            mut drills,
+            mut tiles,
+            mut reveal_queue,
+            mut tile_changes,
+            level_grid,
+            mut ore_storages,
+        ): Self::SystemData,
+    ) {
+        let mut finished: Vec<(Entity, Entity)> = vec![];
+
+        // only entities without a `Path` have arrived at the wall
+        for (entity, drill, _) in (&entities, &mut drills, !&paths).join() {
+            drill.remaining -= time.delta_seconds();
+            if drill.remaining <= 0. {
+                finished.push((entity, drill.wall));
+            }
+        }
+
+        let reveal_queue = (*reveal_queue).as_mut().unwrap();
+        for (entity, wall) in finished {
+            drills.remove(entity);
+
+            // another `RockRaider` may have drilled the same wall already
+            if tiles.get(wall).unwrap().drill_time().is_none() {
+                continue;
+            }
+
+            level_grid.drill_wall(
+                wall,
+                &mut tiles,
+                reveal_queue,
+                time.absolute_time(),
+                &entities,
+                &mut ore_storages,
+            );
+            tile_changes.single_write(TileChanged { tile: wall });
+        }
+    }
+}
+
+/// The order to drill a wall. The `RockRaider` starts drilling as soon as it has no `Path` anymore.
+#[derive(Debug)]
+pub struct Drill {
+    /// The entity of the `Tile::Wall` to drill
+    pub wall: Entity,
+    /// The seconds of drilling left until the wall breaks
+    pub remaining: f32,
+}
+
+impl Drill {
+    pub fn new(wall: Entity, drill_time: f32) -> Self {
+        Drill {
+            wall,
+            remaining: drill_time,
+        }
+    }
+}
+
+impl Component for Drill {
+    type Storage = DenseVecStorage<Self>;
+}
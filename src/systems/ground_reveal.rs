@@ -3,11 +3,12 @@ use amethyst::{
     core::{timing::Time, transform::Transform},
     ecs::prelude::{Entity, Read, ReadExpect, System, Write, WriteStorage},
     renderer::{Material, MaterialDefaults, Mesh, MeshHandle, Texture},
+    shrev::EventChannel,
 };
 
 use entities::Tile;
 use eventhandling::{ClickHandlerComponent, HoverHandlerComponent, Hovered};
-use level::{LevelGrid, TilePatternMap};
+use level::{LevelGrid, TileChanged, TileLookupTable};
 
 use assetmanagement::AssetManager;
 use std::{cmp::Reverse, collections::BinaryHeap, time::Duration};
@@ -26,9 +27,10 @@ impl<'a> System<'a> for GroundRevealSystem {
         Write<'a, Hovered>,
         WriteStorage<'a, HoverHandlerComponent>,
         WriteStorage<'a, ClickHandlerComponent>,
-        Read<'a, TilePatternMap>,
+        Read<'a, TileLookupTable>,
         Read<'a, LevelGrid>,
         Write<'a, Option<RevealQueue>>,
+        Write<'a, EventChannel<TileChanged>>,
         WriteStorage<'a, Transform>,
         WriteStorage<'a, Tile>,
         (
@@ -53,6 +55,7 @@ impl<'a> System<'a> for GroundRevealSystem {
             dict,
             level_grid,
             mut ground_reveal_queue,
+            mut tile_changes,
             mut transforms,
             mut tiles,
             mut storages,
@@ -65,8 +68,11 @@ impl<'a> System<'a> for GroundRevealSystem {
             let Reverse((_, entity)) = ground_reveal_queue.pop().unwrap();
 
             // reveal yourself
-            if !tiles.get_mut(entity).unwrap().reveal() {
-                break;
+            if tiles.get_mut(entity).unwrap().reveal() {
+                tile_changes.single_write(TileChanged { tile: entity });
+            } else {
+                // revealed already, because it was queued more than once
+                continue;
             };
 
             let tran = transforms.get(entity).unwrap().clone();
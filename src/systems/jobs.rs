@@ -0,0 +1,603 @@
+use amethyst::{
+    core::transform::Transform,
+    ecs::prelude::{
+        Component, DenseVecStorage, Entities, Entity, Join, Read, ReadStorage, System, Write,
+        WriteStorage,
+    },
+};
+
+use entities::{Ore, RockRaider, Tile};
+use level::{FlowFields, LevelGrid};
+use systems::{Clear, CollectOre, Construct, Drill, FlowDestination, Path, TeleportOut};
+
+use std::cmp::Reverse;
+
+///
+/// This system hands out the open jobs of the `JobBoard` to idle `RockRaider`s and keeps track of the jobs, that are worked on.
+///
+/// Every idle `RockRaider` takes the reachable job with the highest `Priority`. Among jobs with the same `Priority`, it takes the nearest one.
+/// If several `RockRaider`s want the same job, the nearest one gets it.
+/// A job is finished, as soon as its `RockRaider` has no order left. It is `JobState::Done`, if its goal was reached, and `JobState::Failed` otherwise.
+/// If the player gives the `RockRaider` another order, or the `RockRaider` is gone, its job is open again.
+/// Finished jobs and jobs, whose target is gone, are removed from the `JobBoard` in the same frame.
+///
+pub struct JobSystem;
+
+impl<'a> System<'a> for JobSystem {
+    type SystemData = (
+        Entities<'a>,
+        Read<'a, LevelGrid>,
+        Write<'a, JobBoard>,
+        Write<'a, FlowFields>,
+        ReadStorage<'a, Tile>,
+        ReadStorage<'a, Transform>,
+        ReadStorage<'a, RockRaider>,
+        ReadStorage<'a, Ore>,
+        ReadStorage<'a, FlowDestination>,
+        ReadStorage<'a, TeleportOut>,
+        WriteStorage<'a, AssignedJob>,
+        WriteStorage<'a, Path>,
+        WriteStorage<'a, Drill>,
+        WriteStorage<'a, Clear>,
+        WriteStorage<'a, CollectOre>,
+        WriteStorage<'a, Construct>,
+    );
+
+    fn run(
+        &mut self,
+        (
+            entities,
+            level_grid,
+            mut job_board,
+            mut flow_fields,
+            tiles,
+            transforms,
+            rock_raiders,
+            ores,
+            flow_destinations,
+            teleports,
+            mut assigned_jobs,
+            mut paths,
+            mut drills,
+            mut clears,
+            mut collect_ores,
+            mut constructs,
+        ): Self::SystemData,
+    ) {
+        // the `RockRaider` got another order or is gone
+        for (id, job) in job_board.jobs.iter_mut() {
+            if let Some(rock_raider) = job.state.worker() {
+                if assigned_jobs.get(rock_raider).map(|assigned| assigned.0) != Some(*id) {
+                    job.state = JobState::Open;
+                }
+            }
+        }
+
+        let grid_position_of = |entity: Entity| {
+            LevelGrid::grid_position(transforms.get(entity).unwrap().translation())
+        };
+
+        let mut finished: Vec<Entity> = vec![];
+        for (rock_raider, assigned, _) in (&entities, &assigned_jobs, &rock_raiders).join() {
+            let busy = drills.get(rock_raider).is_some()
+                || clears.get(rock_raider).is_some()
+                || collect_ores.get(rock_raider).is_some()
+                || constructs.get(rock_raider).is_some()
+                || flow_destinations.get(rock_raider).is_some();
+            let job = match job_board.job_mut(assigned.0) {
+                Some(job) => job,
+                // the job was pruned, while the `RockRaider` still held on to it
+                None => {
+                    finished.push(rock_raider);
+                    continue;
+                }
+            };
+
+            if paths.get(rock_raider).is_some() {
+                continue;
+            } else if busy {
+                job.state = JobState::InProgress(rock_raider);
+                continue;
+            }
+
+            let reached = match job.kind {
+                JobKind::Move => grid_position_of(rock_raider) == grid_position_of(job.target),
+                JobKind::Build => {
+                    let (x, y) = grid_position_of(rock_raider);
+                    let (target_x, target_y) = grid_position_of(job.target);
+                    (x - target_x).abs() + (y - target_y).abs() == 1
+                }
+                _ => job.is_achieved(&entities, &tiles),
+            };
+            job.state = if reached {
+                JobState::Done
+            } else {
+                JobState::Failed
+            };
+            finished.push(rock_raider);
+        }
+        for rock_raider in finished {
+            assigned_jobs.remove(rock_raider);
+        }
+
+        // the player did the work by hand already
+        for (_, job) in job_board.jobs.iter_mut() {
+            if job.state == JobState::Open && job.is_achieved(&entities, &tiles) {
+                job.state = JobState::Done;
+            }
+        }
+        job_board.prune(|target| entities.is_alive(target));
+
+        let idle: Vec<(Entity, (i32, i32))> = (
+            &entities,
+            &rock_raiders,
+            &transforms,
+            !&assigned_jobs,
+            !&paths,
+            !&drills,
+            !&clears,
+            !&collect_ores,
+            !&constructs,
+            !&flow_destinations,
+            !&teleports,
+        )
+            .join()
+            .map(|(entity, _, transform, _, _, _, _, _, _, _, _)| {
+                (entity, LevelGrid::grid_position(transform.translation()))
+            })
+            .collect();
+        if idle.is_empty() {
+            return;
+        }
+
+        let assignments = assign_jobs(&mut job_board, &idle, |job, (x, y)| {
+            // a `Tile` that cannot be worked on anymore, or `Ore` that is carried already
+            if !job.is_available(&entities, &tiles, &ores) {
+                return None;
+            }
+            let (target_x, target_y) = grid_position_of(job.target);
+            flow_fields
+                .get(
+                    &job.kind.destinations(target_x, target_y),
+                    &level_grid,
+                    &tiles,
+                )
+                .cost(x, y)
+        });
+
+        for (rock_raider, id) in assignments {
+            // the job was claimed just now, so it is on the board
+            let job = job_board.job_mut(id).unwrap();
+            let (target_x, target_y) = grid_position_of(job.target);
+            let (x, y) = grid_position_of(rock_raider);
+            let start = level_grid.get(x, y).unwrap();
+            let path = level_grid.find_path_to_nearest(
+                start,
+                &job.kind.destinations(target_x, target_y),
+                &tiles,
+                &transforms,
+            );
+
+            let path = match path {
+                Some(path) => path,
+                // the `FlowField` knew a way, so this does not happen
+                None => {
+                    job.state = JobState::Open;
+                    continue;
+                }
+            };
+            paths.insert(rock_raider, path).unwrap();
+            assigned_jobs.insert(rock_raider, AssignedJob(id)).unwrap();
+
+            match job.kind {
+                JobKind::Drill => {
+                    let drill_time = tiles.get(job.target).unwrap().drill_time().unwrap();
+                    drills
+                        .insert(rock_raider, Drill::new(job.target, drill_time))
+                        .unwrap();
+                }
+                JobKind::ClearRubble => {
+                    clears.insert(rock_raider, Clear::new(job.target)).unwrap();
+                }
+                JobKind::CollectOre => {
+                    collect_ores
+                        .insert(rock_raider, CollectOre::Fetch(job.target))
+                        .unwrap();
+                }
+                JobKind::Build => {
+                    constructs
+                        .insert(rock_raider, Construct::ToSite(job.target))
+                        .unwrap();
+                }
+                JobKind::Move => (),
+            }
+        }
+    }
+}
+
+/// Claims an open job for every idle `RockRaider` and returns which `RockRaider` got which job.
+///
+/// `idle` are the `RockRaider`s together with their grid positions. `cost` returns the cost of the way from a grid position to the job, or `None` if the job cannot be reached from there.
+/// Every `RockRaider` takes the job with the highest `Priority`, that it can reach, and the nearest one among those. If several `RockRaider`s want the same job, the nearest one gets it.
+/// Ties are decided by the order of `idle` and the order the jobs were posted in.
+///
+/// This does not touch the world, so the assignment can be tried out on a `JobBoard` alone.
+pub fn assign_jobs<F: FnMut(&Job, (i32, i32)) -> Option<u32>>(
+    job_board: &mut JobBoard,
+    idle: &[(Entity, (i32, i32))],
+    mut cost: F,
+) -> Vec<(Entity, JobId)> {
+    // every open job every `RockRaider` can reach, the most urgent and nearest first
+    let mut candidates = vec![];
+    for &(rock_raider, position) in idle {
+        for (id, job) in job_board.jobs.iter() {
+            if job.state != JobState::Open {
+                continue;
+            }
+            if let Some(cost) = cost(job, position) {
+                let priority = Reverse(job_board.priority(job.kind));
+                candidates.push((priority, cost, rock_raider, *id));
+            }
+        }
+    }
+    candidates.sort_by_key(|&(priority, cost, _, _)| (priority, cost));
+
+    let mut assignments: Vec<(Entity, JobId)> = vec![];
+    for (_, _, rock_raider, id) in candidates {
+        let taken = assignments
+            .iter()
+            .any(|&(other, other_id)| other == rock_raider || other_id == id);
+        if !taken {
+            job_board.job_mut(id).unwrap().state = JobState::Claimed(rock_raider);
+            assignments.push((rock_raider, id));
+        }
+    }
+    assignments
+}
+
+/// The kinds of work a `RockRaider` can do
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum JobKind {
+    /// Walk onto the target `Tile`
+    Move,
+    /// Drill the target `Tile::Wall`
+    Drill,
+    /// Clear the target `Tile::Rubble`
+    ClearRubble,
+    /// Bring the target `Ore` to the `Base`
+    CollectOre,
+    /// Bring ore to the `ConstructionSite` on the target `Tile` and build it
+    Build,
+}
+
+impl JobKind {
+    pub fn all() -> [JobKind; 5] {
+        [
+            JobKind::Move,
+            JobKind::Drill,
+            JobKind::ClearRubble,
+            JobKind::CollectOre,
+            JobKind::Build,
+        ]
+    }
+
+    /// The grid positions, that a `RockRaider` walks to, to do this kind of work on a target at (x, y)
+    pub fn destinations(self, x: i32, y: i32) -> Vec<(i32, i32)> {
+        match self {
+            JobKind::Drill | JobKind::Build => FlowDestination::next_to(x, y).tiles,
+            JobKind::Move | JobKind::ClearRubble | JobKind::CollectOre => vec![(x, y)],
+        }
+    }
+}
+
+/// How urgent the jobs of a `JobKind` are. The player sets one for every `JobKind`.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord)]
+pub enum Priority {
+    Low,
+    Normal,
+    High,
+}
+
+impl Priority {
+    /// The next higher `Priority`. `High` is followed by `Low` again.
+    pub fn next(self) -> Self {
+        match self {
+            Priority::Low => Priority::Normal,
+            Priority::Normal => Priority::High,
+            Priority::High => Priority::Low,
+        }
+    }
+}
+
+impl Default for Priority {
+    fn default() -> Self {
+        Priority::Normal
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum JobState {
+    /// Waiting for an idle `RockRaider`
+    Open,
+    /// The `RockRaider` walks to the job
+    Claimed(Entity),
+    /// The `RockRaider` arrived and works on the job
+    InProgress(Entity),
+    Done,
+    /// The `RockRaider` stopped, before the goal of the job was reached
+    Failed,
+}
+
+impl JobState {
+    /// Whether the job is `Done` or `Failed`
+    pub fn is_finished(self) -> bool {
+        self == JobState::Done || self == JobState::Failed
+    }
+
+    /// The `RockRaider` that claimed the job, if any
+    pub fn worker(self) -> Option<Entity> {
+        match self {
+            JobState::Claimed(rock_raider) | JobState::InProgress(rock_raider) => Some(rock_raider),
+            _ => None,
+        }
+    }
+}
+
+#[derive(Clone, Debug)]
+pub struct Job {
+    pub kind: JobKind,
+    /// The `Tile` or `Ore` the job is about
+    pub target: Entity,
+    pub state: JobState,
+}
+
+impl Job {
+    /// Whether the work is done, no matter by whom. A job to walk somewhere or build something is never achieved without its `RockRaider`.
+    fn is_achieved(&self, entities: &Entities, tiles: &ReadStorage<Tile>) -> bool {
+        match self.kind {
+            JobKind::Drill => tiles.get(self.target).unwrap().drill_time().is_none(),
+            JobKind::ClearRubble => match tiles.get(self.target).unwrap() {
+                Tile::Rubble { .. } => false,
+                _ => true,
+            },
+            // delivered ore is deleted, ore that lies around or is carried still exists
+            JobKind::CollectOre => !entities.is_alive(self.target),
+            JobKind::Move | JobKind::Build => false,
+        }
+    }
+
+    /// Whether a `RockRaider` could start the job right now
+    fn is_available(
+        &self,
+        entities: &Entities,
+        tiles: &ReadStorage<Tile>,
+        ores: &ReadStorage<Ore>,
+    ) -> bool {
+        match self.kind {
+            JobKind::CollectOre => ores.get(self.target).is_some(),
+            _ => !self.is_achieved(entities, tiles),
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct JobId(usize);
+
+/// A `Resource`, that holds all jobs of the level, that were posted by the player.
+///
+/// Finished jobs stay on the board together with their `JobState`, until they are pruned. Ids are never used twice, so an id never refers to another job.
+#[derive(Default)]
+pub struct JobBoard {
+    /// The jobs in the order they were posted, so sorted by their ids
+    jobs: Vec<(JobId, Job)>,
+    /// The id of the next posted job
+    next_id: usize,
+    /// The `Priority` of every `JobKind`, in the order of `JobKind::all`
+    priorities: [Priority; 5],
+}
+
+impl JobBoard {
+    /// Adds an open job. If the same work is posted already and not finished yet, its id is returned instead.
+    pub fn post(&mut self, kind: JobKind, target: Entity) -> JobId {
+        let posted = self
+            .jobs
+            .iter()
+            .find(|(_, job)| job.kind == kind && job.target == target && !job.state.is_finished());
+        if let Some(&(id, _)) = posted {
+            return id;
+        }
+
+        let id = JobId(self.next_id);
+        self.next_id += 1;
+        self.jobs.push((
+            id,
+            Job {
+                kind,
+                target,
+                state: JobState::Open,
+            },
+        ));
+        id
+    }
+
+    /// Returns `None` if the job was pruned already.
+    pub fn job(&self, id: JobId) -> Option<&Job> {
+        self.index(id).map(move |index| &self.jobs[index].1)
+    }
+
+    /// Returns `None` if the job was pruned already.
+    pub fn job_mut(&mut self, id: JobId) -> Option<&mut Job> {
+        match self.index(id) {
+            Some(index) => Some(&mut self.jobs[index].1),
+            None => None,
+        }
+    }
+
+    /// Removes every job, that is finished, and every job nobody works on, whose target is gone. `is_alive` tells whether a target still exists.
+    pub fn prune<F: Fn(Entity) -> bool>(&mut self, is_alive: F) {
+        self.jobs.retain(|(_, job)| {
+            !job.state.is_finished() && (job.state.worker().is_some() || is_alive(job.target))
+        });
+    }
+
+    fn index(&self, id: JobId) -> Option<usize> {
+        self.jobs.binary_search_by_key(&id, |&(id, _)| id).ok()
+    }
+
+    pub fn priority(&self, kind: JobKind) -> Priority {
+        self.priorities[JobBoard::kind_index(kind)]
+    }
+
+    pub fn set_priority(&mut self, kind: JobKind, priority: Priority) {
+        self.priorities[JobBoard::kind_index(kind)] = priority;
+    }
+
+    fn kind_index(kind: JobKind) -> usize {
+        JobKind::all().iter().position(|&k| k == kind).unwrap()
+    }
+}
+
+/// The job of the `JobBoard`, that a `RockRaider` works on
+#[derive(Debug)]
+pub struct AssignedJob(pub JobId);
+
+impl Component for AssignedJob {
+    type Storage = DenseVecStorage<Self>;
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use amethyst::ecs::prelude::{Builder, World};
+
+    fn create_entities(world: &mut World, count: usize) -> Vec<Entity> {
+        (0..count).map(|_| world.create_entity().build()).collect()
+    }
+
+    /// The cost of the way along the x axis to the job's target. Targets, that are not listed with their x coordinate, cannot be reached.
+    fn distance<'a>(
+        targets: &'a [(Entity, i32)],
+    ) -> impl FnMut(&Job, (i32, i32)) -> Option<u32> + 'a {
+        move |job: &Job, (x, _): (i32, i32)| {
+            targets
+                .iter()
+                .find(|&&(target, _)| target == job.target)
+                .map(|&(_, target_x)| (target_x - x).abs() as u32)
+        }
+    }
+
+    #[test]
+    fn takes_the_job_with_the_highest_priority() {
+        let mut world = World::new();
+        let entities = create_entities(&mut world, 3);
+        let (near_target, far_target, rock_raider) = (entities[0], entities[1], entities[2]);
+        let targets = [(near_target, 1), (far_target, 10)];
+
+        let mut job_board = JobBoard::default();
+        let near = job_board.post(JobKind::ClearRubble, near_target);
+        let far = job_board.post(JobKind::Drill, far_target);
+        job_board.set_priority(JobKind::Drill, Priority::High);
+        let assignments = assign_jobs(&mut job_board, &[(rock_raider, (0, 0))], distance(&targets));
+        assert_eq!(assignments, vec![(rock_raider, far)]);
+        assert_eq!(
+            job_board.job(far).unwrap().state,
+            JobState::Claimed(rock_raider)
+        );
+        assert_eq!(job_board.job(near).unwrap().state, JobState::Open);
+
+        // with the same `Priority`, the nearer job is taken
+        let mut job_board = JobBoard::default();
+        let near = job_board.post(JobKind::ClearRubble, near_target);
+        job_board.post(JobKind::Drill, far_target);
+        let assignments = assign_jobs(&mut job_board, &[(rock_raider, (0, 0))], distance(&targets));
+        assert_eq!(assignments, vec![(rock_raider, near)]);
+    }
+
+    #[test]
+    fn the_nearest_idle_rock_raider_gets_the_job() {
+        let mut world = World::new();
+        let entities = create_entities(&mut world, 3);
+        let (target, far_raider, near_raider) = (entities[0], entities[1], entities[2]);
+
+        let mut job_board = JobBoard::default();
+        let id = job_board.post(JobKind::Drill, target);
+        let idle = [(far_raider, (0, 0)), (near_raider, (4, 0))];
+        let assignments = assign_jobs(&mut job_board, &idle, distance(&[(target, 5)]));
+        assert_eq!(assignments, vec![(near_raider, id)]);
+    }
+
+    #[test]
+    fn hands_out_a_job_only_once() {
+        let mut world = World::new();
+        let entities = create_entities(&mut world, 3);
+        let (target, first, second) = (entities[0], entities[1], entities[2]);
+        let targets = [(target, 5)];
+
+        let mut job_board = JobBoard::default();
+        let id = job_board.post(JobKind::Drill, target);
+        assert_eq!(job_board.post(JobKind::Drill, target), id);
+
+        let idle = [(first, (0, 0)), (second, (0, 0))];
+        let assignments = assign_jobs(&mut job_board, &idle, distance(&targets));
+        assert_eq!(assignments, vec![(first, id)]);
+        assert!(assign_jobs(&mut job_board, &[(second, (0, 0))], distance(&targets)).is_empty());
+    }
+
+    #[test]
+    fn skips_unreachable_jobs() {
+        let mut world = World::new();
+        let entities = create_entities(&mut world, 3);
+        let (reachable_target, unreachable_target, rock_raider) =
+            (entities[0], entities[1], entities[2]);
+        let targets = [(reachable_target, 10)];
+
+        let mut job_board = JobBoard::default();
+        let unreachable = job_board.post(JobKind::Drill, unreachable_target);
+        job_board.set_priority(JobKind::Drill, Priority::High);
+        let reachable = job_board.post(JobKind::ClearRubble, reachable_target);
+        let assignments = assign_jobs(&mut job_board, &[(rock_raider, (0, 0))], distance(&targets));
+        assert_eq!(assignments, vec![(rock_raider, reachable)]);
+        assert_eq!(job_board.job(unreachable).unwrap().state, JobState::Open);
+
+        let mut job_board = JobBoard::default();
+        job_board.post(JobKind::Drill, unreachable_target);
+        assert!(
+            assign_jobs(&mut job_board, &[(rock_raider, (0, 0))], distance(&targets)).is_empty()
+        );
+    }
+
+    #[test]
+    fn prunes_finished_jobs_and_jobs_whose_target_is_gone() {
+        let mut world = World::new();
+        let entities = create_entities(&mut world, 4);
+        let (tile, ore, carried_ore, rock_raider) =
+            (entities[0], entities[1], entities[2], entities[3]);
+
+        let mut job_board = JobBoard::default();
+        let done = job_board.post(JobKind::Drill, tile);
+        let failed = job_board.post(JobKind::ClearRubble, tile);
+        job_board.post(JobKind::CollectOre, ore);
+        let claimed = job_board.post(JobKind::CollectOre, carried_ore);
+        let open = job_board.post(JobKind::Move, tile);
+        job_board.job_mut(done).unwrap().state = JobState::Done;
+        job_board.job_mut(failed).unwrap().state = JobState::Failed;
+        job_board.job_mut(claimed).unwrap().state = JobState::Claimed(rock_raider);
+        world.delete_entity(ore).unwrap();
+        world.delete_entity(carried_ore).unwrap();
+
+        job_board.prune(|target| world.is_alive(target));
+        assert_eq!(job_board.jobs.len(), 2);
+        assert!(job_board.job(done).is_none());
+        assert_eq!(job_board.job(open).unwrap().kind, JobKind::Move);
+        // the `RockRaider` finishes its job first
+        assert_eq!(
+            job_board.job(claimed).unwrap().state,
+            JobState::Claimed(rock_raider)
+        );
+
+        // the ids of pruned jobs are not used again
+        let posted_again = job_board.post(JobKind::Drill, tile);
+        assert!(posted_again != done && posted_again != open && posted_again != claimed);
+        assert_eq!(job_board.job(posted_again).unwrap().state, JobState::Open);
+    }
+}
@@ -0,0 +1,52 @@
+use amethyst::{
+    core::{timing::Time, transform::Transform},
+    ecs::prelude::{Entities, Join, Read, ReadStorage, System, Write},
+    shrev::EventChannel,
+};
+
+use entities::Tile;
+use level::LevelGrid;
+use systems::{Damage, Health};
+
+/// The health a `RockRaider` loses per second, while it stands next to `Tile::Lava`
+const LAVA_DAMAGE_PER_SECOND: f32 = 10.;
+
+///
+/// This system hurts everything with `Health`, that stands on or next to a `Tile::Lava`. Diagonal neighbors count as well.
+///
+pub struct LavaSystem;
+
+impl<'a> System<'a> for LavaSystem {
+    type SystemData = (
+        Entities<'a>,
+        Read<'a, Time>,
+        Read<'a, LevelGrid>,
+        ReadStorage<'a, Tile>,
+        ReadStorage<'a, Transform>,
+        ReadStorage<'a, Health>,
+        Write<'a, EventChannel<Damage>>,
+    );
+
+    fn run(
+        &mut self,
+        (entities, time, level_grid, tiles, transforms, healths, mut damages): Self::SystemData,
+    ) {
+        for (entity, transform, _) in (&entities, &transforms, &healths).join() {
+            let (x, y) = LevelGrid::grid_position(transform.translation());
+
+            let next_to_lava = (x - 1..=x + 1)
+                .flat_map(|x| (y - 1..=y + 1).map(move |y| (x, y)))
+                .any(|(x, y)| match level_grid.get_tile(x, y, &tiles) {
+                    Some(Tile::Lava) => true,
+                    _ => false,
+                });
+
+            if next_to_lava {
+                damages.single_write(Damage {
+                    target: entity,
+                    amount: LAVA_DAMAGE_PER_SECOND * time.delta_seconds(),
+                });
+            }
+        }
+    }
+}
@@ -1,7 +1,34 @@
+mod carrying;
+mod clearing;
+mod construction;
+mod drilling;
 mod ground_reveal;
+mod jobs;
+mod lava;
 mod movement;
+mod ore_collection;
 mod oxygen;
+mod placement_preview;
+mod repathing;
+mod spawning;
+mod steering;
+mod teleport;
+mod vitals;
 
+pub use self::carrying::CarrySlot;
+pub use self::clearing::{Clear, ClearingSystem};
+pub use self::construction::{Construct, ConstructionSystem};
+pub use self::drilling::{Drill, DrillingSystem};
 pub use self::ground_reveal::{GroundRevealSystem, RevealQueue};
-pub use self::movement::{MovementSystem, Path};
+pub use self::jobs::{
+    assign_jobs, AssignedJob, Job, JobBoard, JobId, JobKind, JobState, JobSystem, Priority,
+};
+pub use self::lava::LavaSystem;
+pub use self::movement::{FlowDestination, MoveSpeed, MovementSystem, Path};
+pub use self::ore_collection::{CollectOre, OreCollectionSystem, OreStockpile};
 pub use self::oxygen::{Oxygen, OxygenBar, OxygenSystem};
+pub use self::placement_preview::{PlacementMode, PlacementPreviewSystem};
+pub use self::repathing::RepathingSystem;
+pub use self::spawning::{SpawnQueue, SpawnSystem, MAX_RAIDERS, SPAWN_DURATION};
+pub use self::teleport::{TeleportOut, TeleportSystem};
+pub use self::vitals::{Damage, Health, VitalsSystem};
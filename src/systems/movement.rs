@@ -5,43 +5,99 @@ use amethyst::{
         transform::Transform,
     },
     ecs::prelude::{
-        Component, DenseVecStorage, Entities, Entity, Join, Read, System, WriteStorage,
+        Component, DenseVecStorage, Entities, Entity, Join, Read, ReadStorage, System, Write,
+        WriteStorage,
     },
 };
 
+use entities::{RockRaider, Tile};
+use level::{FlowFields, LevelGrid};
+use systems::steering::{free_slot, keep_apart};
+
+use std::f32::consts::PI;
+
+///
+/// This system moves every entity along its `Path`.
+/// Entities without a `Path`, that have a `FlowDestination`, follow the `FlowField` towards it instead.
+///
+/// Walking `RockRaider`s keep apart from each other. When they arrive, they take a free slot on their destination `Tile`, so several of them can stand on it without overlapping.
+///
 pub struct MovementSystem;
 
 impl<'a> System<'a> for MovementSystem {
     type SystemData = (
         Entities<'a>,
         Read<'a, Time>,
+        Read<'a, LevelGrid>,
+        ReadStorage<'a, Tile>,
+        ReadStorage<'a, RockRaider>,
+        ReadStorage<'a, MoveSpeed>,
+        Write<'a, FlowFields>,
         WriteStorage<'a, Path>,
+        WriteStorage<'a, FlowDestination>,
         WriteStorage<'a, Transform>,
     );
 
-    fn run(&mut self, (entities, time, mut path_storage, mut transforms): Self::SystemData) {
+    fn run(
+        &mut self,
+        (
+            entities,
+            time,
+            level_grid,
+            tiles,
+            rock_raiders,
+            move_speeds,
+            mut flow_fields,
+            mut path_storage,
+            mut flow_destinations,
+            mut transforms,
+        ): Self::SystemData,
+    ) {
+        let raiders: Vec<(Entity, Vector3<f32>)> = (&entities, &rock_raiders, &transforms)
+            .join()
+            .map(|(entity, _, transform)| (entity, *transform.translation()))
+            .collect();
+        let standing: Vec<(Entity, Vector3<f32>)> = (
+            &entities,
+            &rock_raiders,
+            &transforms,
+            !&path_storage,
+            !&flow_destinations,
+        )
+            .join()
+            .map(|(entity, _, transform, _, _)| (entity, *transform.translation()))
+            .collect();
+
         let mut removable_paths: Vec<Entity> = vec![];
 
         for (entity, mut path, mut transform) in
             (&entities, &mut path_storage, &mut transforms).join()
         {
-            let next_destination = path[0];
-            let next_destination = Vector3::new(next_destination.x, 0.0, next_destination.y);
-
-            // shouldn't this be reversed?
-            // BUT IT WORKS, SO IT STAYS until there is a solution :)
-            let direction = transform.translation() - next_destination;
-
-            *transform.rotation_mut() = UnitQuaternion::from_axis_angle(
-                &Vector3::<f32>::y_axis(),
-                Real::atan2(direction.x, direction.z),
-            );
-
-            transform.move_forward(time.delta_seconds());
+            // the slot is chosen once, otherwise it would jump around while the others move
+            if path.len() == 1 && !path.slot_taken {
+                let (x, y) = LevelGrid::grid_position(&Vector3::new(path[0].x, 0.0, path[0].y));
+                path[0] = free_slot(x, y, &standing);
+                path.slot_taken = true;
+            }
 
-            if (next_destination - transform.translation()).magnitude() < time.delta_seconds() {
+            if walk_towards(
+                &mut transform,
+                path[0],
+                &move_speeds.get(entity).cloned().unwrap_or_default(),
+                &level_grid,
+                &tiles,
+                time.delta_seconds(),
+            ) {
                 path.remove(0);
             }
+            keep_apart(
+                entity,
+                &mut transform,
+                &raiders,
+                &level_grid,
+                &tiles,
+                time.delta_seconds(),
+            );
 
             if path.is_empty() {
                 removable_paths.push(entity);
@@ -54,12 +110,187 @@ impl<'a> System<'a> for MovementSystem {
         for e in removable_paths.iter() {
             path_storage.remove(*e);
         }
+
+        let mut arrived: Vec<Entity> = vec![];
+
+        for (entity, destination, mut transform, _) in (
+            &entities,
+            &mut flow_destinations,
+            &mut transforms,
+            !&path_storage,
+        )
+            .join()
+        {
+            let (x, y) = LevelGrid::grid_position(transform.translation());
+            let flow_field = flow_fields.get(&destination.tiles, &level_grid, &tiles);
+
+            let (next_step, last_step) = match flow_field.next_step(x, y) {
+                Some(next_step) => (Point2::new(next_step.0 as f32, next_step.1 as f32), false),
+                // standing on a destination, so only the way to a free slot is left
+                None if flow_field.cost(x, y) == Some(0) => {
+                    let slot = *destination
+                        .slot
+                        .get_or_insert_with(|| free_slot(x, y, &standing));
+                    (slot, true)
+                }
+                None => {
+                    warn!("Destination unreachable: {:?} stops walking", entity);
+                    arrived.push(entity);
+                    continue;
+                }
+            };
+
+            let reached = walk_towards(
+                &mut transform,
+                next_step,
+                &move_speeds.get(entity).cloned().unwrap_or_default(),
+                &level_grid,
+                &tiles,
+                time.delta_seconds(),
+            );
+            keep_apart(
+                entity,
+                &mut transform,
+                &raiders,
+                &level_grid,
+                &tiles,
+                time.delta_seconds(),
+            );
+            if reached && last_step {
+                arrived.push(entity);
+            }
+        }
+
+        for e in arrived.iter() {
+            flow_destinations.remove(*e);
+        }
+    }
+}
+
+/// Moves the entity straight towards the destination and turns it towards the direction it walks in. Returns whether the destination is reached.
+///
+/// The entity never walks past the destination, so arriving does not depend on the frame rate.
+fn walk_towards(
+    transform: &mut Transform,
+    destination: Point2<f32>,
+    move_speed: &MoveSpeed,
+    level_grid: &LevelGrid,
+    tiles: &ReadStorage<Tile>,
+    delta_seconds: f32,
+) -> bool {
+    let destination = Vector3::new(destination.x, 0.0, destination.y);
+    let direction = destination - transform.translation();
+    let distance = direction.norm();
+    if distance == 0. {
+        return true;
+    }
+
+    turn_towards(transform, &direction, move_speed.turn_rate * delta_seconds);
+
+    // rough terrain like rubble slows down
+    let (x, y) = LevelGrid::grid_position(transform.translation());
+    let movement_cost = level_grid
+        .get_tile(x, y, tiles)
+        .and_then(|tile| tile.movement_cost())
+        .unwrap_or(1.);
+    let step = move_speed.speed * delta_seconds / movement_cost;
+
+    if step >= distance {
+        transform.set_position(destination);
+        return true;
+    }
+    let position = transform.translation() + direction / distance * step;
+    transform.set_position(position);
+    false
+}
+
+/// Turns the entity around the y axis towards the direction, but by `max_angle` radians at most.
+fn turn_towards(transform: &mut Transform, direction: &Vector3<f32>, max_angle: f32) {
+    // the models look along their negative z axis, which is the direction `Transform::move_forward` would move them in
+    let target_heading = Real::atan2(-direction.x, -direction.z);
+    let local_z = transform.rotation() * Vector3::z();
+    let heading = Real::atan2(local_z.x, local_z.z);
+
+    // turn the shorter way round
+    let mut difference = target_heading - heading;
+    while difference > PI {
+        difference -= 2. * PI;
+    }
+    while difference < -PI {
+        difference += 2. * PI;
+    }
+
+    *transform.rotation_mut() = UnitQuaternion::from_axis_angle(
+        &Vector3::<f32>::y_axis(),
+        heading + difference.max(-max_angle).min(max_angle),
+    );
+}
+
+/// How fast an entity walks and turns. Entities without a `MoveSpeed` move like a `RockRaider`.
+#[derive(Clone, Debug)]
+pub struct MoveSpeed {
+    /// `Tile`s per second on plain ground
+    pub speed: f32,
+    /// Radians per second
+    pub turn_rate: f32,
+}
+
+impl MoveSpeed {
+    pub fn new(speed: f32, turn_rate: f32) -> Self {
+        MoveSpeed { speed, turn_rate }
+    }
+}
+
+impl Default for MoveSpeed {
+    /// The speed of a `RockRaider`. Vehicles bring their own.
+    fn default() -> Self {
+        MoveSpeed::new(1., 3. * PI)
+    }
+}
+
+impl Component for MoveSpeed {
+    type Storage = DenseVecStorage<Self>;
+}
+
+/// The order to walk to the nearest of some `Tile`s along the shared `FlowField` towards them.
+///
+/// Use this instead of a `Path`, when many entities are heading to the same place.
+#[derive(Debug)]
+pub struct FlowDestination {
+    /// The grid positions of the destination `Tile`s
+    pub tiles: Vec<(i32, i32)>,
+    /// The slot on the destination `Tile`, once the entity stands on it
+    slot: Option<Point2<f32>>,
+}
+
+impl FlowDestination {
+    /// The `Tile`s directly next to the position (x, y). This is used to walk up to something, like the `Base`.
+    pub fn next_to(x: i32, y: i32) -> Self {
+        FlowDestination {
+            tiles: vec![(x + 1, y), (x - 1, y), (x, y + 1), (x, y - 1)],
+            slot: None,
+        }
     }
 }
 
+impl Component for FlowDestination {
+    type Storage = DenseVecStorage<Self>;
+}
+
 #[derive(Debug)]
 pub struct Path {
     pub path: Vec<Point2<f32>>,
+    /// Whether the last waypoint was moved onto a free slot of its `Tile` already. See `free_slot`
+    slot_taken: bool,
+}
+
+impl Path {
+    pub fn new(path: Vec<Point2<f32>>) -> Self {
+        Path {
+            path,
+            slot_taken: false,
+        }
+    }
 }
 
 impl Component for Path {
@@ -80,3 +311,128 @@ impl DerefMut for Path {
         &mut self.path
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use amethyst::{
+        core::transform::GlobalTransform,
+        ecs::prelude::{Builder, RunNow, World},
+    };
+
+    const DELTA_SECONDS: f32 = 0.1;
+
+    /// A `World` with a cave of plain ground, in which every frame lasts `delta_seconds`
+    fn setup(delta_seconds: f32) -> World {
+        let mut world = World::new();
+        world.register::<GlobalTransform>();
+        MovementSystem.setup(&mut world.res);
+        let level_grid = LevelGrid::from_grid(
+            vec![vec![Tile::Ground { concealed: false }; 10]; 10],
+            &mut world,
+        );
+        world.add_resource(level_grid);
+        world
+            .write_resource::<Time>()
+            .set_delta_seconds(delta_seconds);
+        world
+    }
+
+    /// Creates an entity at (0, 0), that walks along the waypoints
+    fn walker(world: &mut World, waypoints: &[(f32, f32)]) -> Entity {
+        world
+            .create_entity()
+            .with(Transform::default())
+            .with(Path::new(
+                waypoints.iter().map(|&(x, y)| Point2::new(x, y)).collect(),
+            ))
+            .build()
+    }
+
+    fn transform(world: &World, entity: Entity) -> Transform {
+        world
+            .read_storage::<Transform>()
+            .get(entity)
+            .unwrap()
+            .clone()
+    }
+
+    #[test]
+    fn never_walks_past_a_waypoint() {
+        let mut world = setup(0.3);
+        let waypoints = [(0., 0.), (1., 0.), (2., 0.), (3., 0.)];
+        let entity = walker(&mut world, &waypoints);
+
+        let mut previous = *transform(&world, entity).translation();
+        for _ in 0..20 {
+            MovementSystem.run_now(&world.res);
+            let current = *transform(&world, entity).translation();
+            for &(x, _) in waypoints.iter() {
+                assert!(
+                    !(previous.x < x && x < current.x),
+                    "walked past the waypoint at {} from {} to {}",
+                    x,
+                    previous.x,
+                    current.x
+                );
+            }
+            previous = current;
+        }
+
+        // the last waypoint was moved onto a free slot of its `Tile`
+        assert!(world.read_storage::<Path>().get(entity).is_none());
+        assert_eq!(LevelGrid::grid_position(&previous), (3, 0));
+    }
+
+    #[test]
+    fn stops_at_the_waypoint_in_a_long_frame() {
+        let mut world = setup(10.);
+        let entity = walker(&mut world, &[(1., 0.), (2., 0.), (3., 0.)]);
+
+        MovementSystem.run_now(&world.res);
+        assert_eq!(
+            *transform(&world, entity).translation(),
+            Vector3::new(1., 0., 0.)
+        );
+    }
+
+    #[test]
+    fn walks_as_fast_as_its_move_speed() {
+        let mut world = setup(DELTA_SECONDS);
+        let walking = walker(&mut world, &[(5., 0.), (6., 0.)]);
+        let running = walker(&mut world, &[(5., 0.), (6., 0.)]);
+        world
+            .write_storage::<MoveSpeed>()
+            .insert(running, MoveSpeed::new(2.5, 3. * PI))
+            .unwrap();
+
+        for frame in 1..=10 {
+            MovementSystem.run_now(&world.res);
+            let seconds = frame as f32 * DELTA_SECONDS;
+            let walked = transform(&world, walking).translation().x;
+            let ran = transform(&world, running).translation().x;
+            assert!((walked - MoveSpeed::default().speed * seconds).abs() < 1e-4);
+            assert!((ran - 2.5 * seconds).abs() < 1e-4);
+        }
+    }
+
+    #[test]
+    fn turns_by_its_turn_rate_at_most() {
+        let mut world = setup(DELTA_SECONDS);
+        // the entity looks along the negative z axis, so the waypoint is right behind it
+        let entity = walker(&mut world, &[(0., 5.), (0., 6.)]);
+        let max_angle = MoveSpeed::default().turn_rate * DELTA_SECONDS;
+
+        let mut previous = *transform(&world, entity).rotation();
+        for _ in 0..10 {
+            MovementSystem.run_now(&world.res);
+            let current = *transform(&world, entity).rotation();
+            assert!((previous.inverse() * current).angle() <= max_angle + 1e-4);
+            previous = current;
+        }
+
+        // it turned around completely and looks at the waypoint now
+        let looking_at = previous * -Vector3::z();
+        assert!((looking_at - Vector3::z()).norm() < 1e-4);
+    }
+}
@@ -0,0 +1,183 @@
+use amethyst::{
+    core::transform::{Parent, Transform},
+    ecs::prelude::{
+        Component, DenseVecStorage, Entities, Entity, Join, Read, ReadStorage, System, World,
+        Write, WriteStorage,
+    },
+};
+
+use entities::{buildings::Base, Ore, Tile};
+use level::{FlowFields, LevelGrid};
+use systems::{CarrySlot, FlowDestination, Path};
+
+///
+/// This system lets `RockRaider`s pick up the `Ore` they were sent to into their `CarrySlot` and carry it to the `Base`, where it is added to the `OreStockpile`.
+/// All `RockRaider`s carrying ore share the `FlowField` towards the `Base`.
+///
+pub struct OreCollectionSystem;
+
+impl<'a> System<'a> for OreCollectionSystem {
+    type SystemData = (
+        Entities<'a>,
+        Read<'a, LevelGrid>,
+        Write<'a, FlowFields>,
+        ReadStorage<'a, Tile>,
+        ReadStorage<'a, Base>,
+        WriteStorage<'a, Parent>,
+        WriteStorage<'a, Ore>,
+        ReadStorage<'a, Path>,
+        WriteStorage<'a, FlowDestination>,
+        WriteStorage<'a, CollectOre>,
+        WriteStorage<'a, CarrySlot>,
+        WriteStorage<'a, Transform>,
+        Write<'a, OreStockpile>,
+    );
+
+    fn run(
+        &mut self,
+        (
+            entities,
+            level_grid,
+            mut flow_fields,
+            tiles,
+            bases,
+            mut parents,
+            mut ores,
+            paths,
+            mut flow_destinations,
+            mut orders,
+            mut carry_slots,
+            mut transforms,
+            mut stockpile,
+        ): Self::SystemData,
+    ) {
+        // the `Tile` the `Base` is built on
+        let base_tile = (&bases, &parents)
+            .join()
+            .map(|(_, parent)| parent.entity)
+            .next();
+
+        // only entities without a `Path` or `FlowDestination` have arrived
+        let arrived: Vec<(Entity, CollectOre)> = (&entities, &orders, !&paths, !&flow_destinations)
+            .join()
+            .map(|(entity, order, _, _)| (entity, *order))
+            .collect();
+
+        for (entity, order) in arrived {
+            orders.remove(entity);
+            let position = *transforms.get(entity).unwrap().translation();
+            let (x, y) = LevelGrid::grid_position(&position);
+            let carry_slot = carry_slots.get_mut(entity).unwrap();
+
+            match order {
+                CollectOre::Fetch(ore) => {
+                    // the `RockRaider` was sent to another piece of ore, before it delivered the one it carries
+                    if let Some(carried) =
+                        carry_slot.drop_off(&position, &mut parents, &mut transforms)
+                    {
+                        ores.insert(carried, Ore).unwrap();
+                    }
+
+                    // somebody else was faster
+                    if ores.get(ore).is_none() {
+                        continue;
+                    }
+                    // the `RockRaider` was sent somewhere else in the meantime
+                    if LevelGrid::grid_position(transforms.get(ore).unwrap().translation())
+                        != (x, y)
+                    {
+                        continue;
+                    }
+
+                    let destination = base_tile
+                        .map(|base| {
+                            let (base_x, base_y) = LevelGrid::grid_position(
+                                transforms.get(base).unwrap().translation(),
+                            );
+                            FlowDestination::next_to(base_x, base_y)
+                        })
+                        .filter(|destination| {
+                            flow_fields
+                                .get(&destination.tiles, &level_grid, &tiles)
+                                .cost(x, y)
+                                .is_some()
+                        });
+                    if let Some(destination) = destination {
+                        ores.remove(ore);
+                        carry_slot.pick_up(entity, ore, &mut parents, &mut transforms);
+                        flow_destinations.insert(entity, destination).unwrap();
+                        orders.insert(entity, CollectOre::Deliver).unwrap();
+                    }
+                }
+                CollectOre::Deliver => {
+                    let at_base = base_tile.map_or(false, |base| {
+                        let (base_x, base_y) =
+                            LevelGrid::grid_position(transforms.get(base).unwrap().translation());
+                        (base_x - x).abs() + (base_y - y).abs() <= 1
+                    });
+
+                    if at_base {
+                        if let Some(ore) = carry_slot.hand_over(&mut parents) {
+                            stockpile.ore += 1;
+                            entities.delete(ore).unwrap();
+                            debug!("Ore delivered. Stockpile: {}", stockpile.ore);
+                        }
+                    } else if let Some(ore) =
+                        carry_slot.drop_off(&position, &mut parents, &mut transforms)
+                    {
+                        // the `RockRaider` was sent somewhere else, so it puts the ore down
+                        ores.insert(ore, Ore).unwrap();
+                    }
+                }
+            }
+        }
+    }
+}
+
+/// The order to bring a piece of `Ore` to the `Base`.
+#[derive(Clone, Copy, Debug)]
+pub enum CollectOre {
+    /// The `RockRaider` walks to the `Ore` to pick it up
+    Fetch(Entity),
+    /// The `RockRaider` carries the `Ore` in its `CarrySlot` to the `Base`
+    Deliver,
+}
+
+impl CollectOre {
+    /// Cancels the order of the `RockRaider`, if it has one. The `Ore` it carries is put down where it stands.
+    pub fn cancel(rock_raider: Entity, world: &World) {
+        if world
+            .write_storage::<CollectOre>()
+            .remove(rock_raider)
+            .is_none()
+        {
+            return;
+        }
+
+        let mut transforms = world.write_storage::<Transform>();
+        let position = *transforms.get(rock_raider).unwrap().translation();
+        let ore = world
+            .write_storage::<CarrySlot>()
+            .get_mut(rock_raider)
+            .and_then(|carry_slot| {
+                carry_slot.drop_off(
+                    &position,
+                    &mut world.write_storage::<Parent>(),
+                    &mut transforms,
+                )
+            });
+        if let Some(ore) = ore {
+            world.write_storage::<Ore>().insert(ore, Ore).unwrap();
+        }
+    }
+}
+
+impl Component for CollectOre {
+    type Storage = DenseVecStorage<Self>;
+}
+
+/// A `Resource`, that holds all the ore that has been delivered to the `Base` during the level.
+#[derive(Default)]
+pub struct OreStockpile {
+    pub ore: u32,
+}
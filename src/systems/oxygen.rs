@@ -3,9 +3,14 @@ use amethyst::{
     ecs::prelude::{Entities, Entity, Read, ReadStorage, System, Write, WriteStorage},
     ui::UiTransform,
 };
-use entities::RockRaider;
+use entities::{buildings::SupportStation, RockRaider};
+use level::LevelInfo;
 use util::{amount_in, find_ui_by_name};
 
+///
+/// This system lets every `RockRaider` breathe oxygen and every `SupportStation` produce it, at the `OxygenRates` of the level.
+/// The cave never holds more than its `Oxygen::max_oxygen`.
+///
 pub struct OxygenSystem;
 
 impl<'a> System<'a> for OxygenSystem {
@@ -13,18 +18,33 @@ impl<'a> System<'a> for OxygenSystem {
         Write<'a, Option<OxygenBar>>,
         Read<'a, Time>,
         Write<'a, Option<Oxygen>>,
+        Read<'a, LevelInfo>,
         ReadStorage<'a, RockRaider>,
+        ReadStorage<'a, SupportStation>,
         WriteStorage<'a, UiTransform>,
         Entities<'a>,
     );
 
     fn run(
         &mut self,
-        (mut ui, time, mut oxygen, rock_raiders, mut ui_transforms, entities): Self::SystemData,
+        (
+            mut ui,
+            time,
+            mut oxygen,
+            level_info,
+            rock_raiders,
+            support_stations,
+            mut ui_transforms,
+            entities,
+        ): Self::SystemData,
     ) {
         let oxygen = (*oxygen).as_mut().unwrap();
-        let breathed_oxygen = amount_in(&rock_raiders) as f32 * time.delta_seconds();
-        oxygen.remaining_oxygen -= breathed_oxygen;
+        let rates = &level_info.oxygen_rates;
+        let breathed_oxygen = amount_in(&rock_raiders) as f32 * rates.per_raider;
+        let produced_oxygen = amount_in(&support_stations) as f32 * rates.per_support_station;
+        oxygen.remaining_oxygen = (oxygen.remaining_oxygen
+            + (produced_oxygen - breathed_oxygen) * time.delta_seconds())
+        .min(oxygen.max_oxygen);
 
         if oxygen.remaining_oxygen <= 0. {
             panic!("No oxygen left for Breathing. You lost");
@@ -0,0 +1,166 @@
+use amethyst::{
+    assets::{AssetStorage, Loader},
+    core::{
+        nalgebra::Vector3,
+        transform::{GlobalTransform, ParentHierarchy, Transform},
+    },
+    ecs::prelude::{Entity, Read, ReadStorage, System, World, WriteStorage},
+    renderer::{Material, PngFormat, Texture, TextureHandle, TextureMetadata},
+};
+
+use assetmanagement::{util::insert_into_asset_storages_with_texture, AssetManager};
+use entities::{
+    buildings::{check_placement, BuildingType, Facing},
+    Tile,
+};
+use eventhandling::Hovered;
+use level::LevelGrid;
+use systems::OreStockpile;
+
+/// The texture of the ghost, when the building can be placed on the hovered `Tile`
+const GHOST_VALID_TEXTURE: &str = "buildings/ghost_valid";
+
+/// The texture of the ghost, when the building cannot be placed on the hovered `Tile`
+const GHOST_INVALID_TEXTURE: &str = "buildings/ghost_invalid";
+
+///
+/// This system moves the ghost of the building, that the player is about to place, onto the hovered `Tile` and turns it to the `Facing` the player chose.
+/// Its texture shows, whether the building can be placed there. See `check_placement`
+///
+pub struct PlacementPreviewSystem;
+
+impl<'a> System<'a> for PlacementPreviewSystem {
+    type SystemData = (
+        Read<'a, Option<PlacementMode>>,
+        Read<'a, Hovered>,
+        Read<'a, LevelGrid>,
+        Read<'a, ParentHierarchy>,
+        Read<'a, OreStockpile>,
+        ReadStorage<'a, Tile>,
+        WriteStorage<'a, Transform>,
+        WriteStorage<'a, Material>,
+    );
+
+    fn run(
+        &mut self,
+        (
+            placement_mode,
+            hovered,
+            level_grid,
+            hierarchy,
+            stockpile,
+            tiles,
+            mut transforms,
+            mut materials,
+        ): Self::SystemData,
+    ) {
+        let mode = match &*placement_mode {
+            Some(mode) => mode,
+            None => return,
+        };
+        // the ghost stays where it is, while something else than a `Tile` is hovered
+        let tile = match **hovered {
+            Some(entity) if tiles.get(entity).is_some() => entity,
+            _ => return,
+        };
+
+        let position = *transforms.get(tile).unwrap().translation();
+        {
+            let ghost = transforms.get_mut(mode.ghost).unwrap();
+            ghost.set_position(position + mode.building.center_offset(mode.facing));
+            *ghost.rotation_mut() = mode.facing.rotation();
+        }
+
+        let (x, y) = LevelGrid::grid_position(&position);
+        let valid = check_placement(
+            mode.building,
+            x,
+            y,
+            mode.facing,
+            &level_grid,
+            &tiles,
+            &hierarchy,
+            &stockpile,
+        )
+        .is_ok();
+        materials.get_mut(mode.ghost).unwrap().albedo = if valid {
+            mode.valid_texture.clone()
+        } else {
+            mode.invalid_texture.clone()
+        };
+    }
+}
+
+/// A `Resource`, that holds the building the player is about to place, while a click on a `Tile` places it instead of giving orders.
+pub struct PlacementMode {
+    pub building: BuildingType,
+    /// The direction the entrance of the building will face
+    pub facing: Facing,
+    /// The entity, that previews the building on the hovered `Tile`
+    pub ghost: Entity,
+    valid_texture: TextureHandle,
+    invalid_texture: TextureHandle,
+}
+
+impl PlacementMode {
+    /// Starts placing the building and creates its ghost.
+    pub fn start(building: BuildingType, world: &World) -> Self {
+        // hidden below the cave, until a `Tile` is hovered
+        let mut transform = Transform::default();
+        transform.set_position(Vector3::new(0., -10., 0.));
+
+        let ghost = world
+            .entities()
+            .build_entity()
+            .with(transform, &mut world.write_storage::<Transform>())
+            .with(
+                GlobalTransform::default(),
+                &mut world.write_storage::<GlobalTransform>(),
+            )
+            .build();
+
+        {
+            let mut storages = world.system_data();
+            insert_into_asset_storages_with_texture(
+                ghost,
+                building.asset_name(),
+                GHOST_VALID_TEXTURE,
+                &mut storages,
+            );
+        }
+
+        let valid_texture = world
+            .read_storage::<Material>()
+            .get(ghost)
+            .unwrap()
+            .albedo
+            .clone();
+        let invalid_texture = world
+            .write_resource::<AssetManager<Texture>>()
+            .get_asset_handle_or_load(
+                GHOST_INVALID_TEXTURE,
+                PngFormat,
+                TextureMetadata::srgb(),
+                &mut world.write_resource::<AssetStorage<Texture>>(),
+                &world.read_resource::<Loader>(),
+            );
+
+        PlacementMode {
+            building,
+            facing: Facing::default(),
+            ghost,
+            valid_texture,
+            invalid_texture,
+        }
+    }
+
+    /// Turns the building clockwise by a quarter.
+    pub fn rotate(&mut self) {
+        self.facing = self.facing.next();
+    }
+
+    /// Stops placing and deletes the ghost.
+    pub fn stop(self, world: &World) {
+        world.entities().delete(self.ghost).unwrap();
+    }
+}
@@ -0,0 +1,108 @@
+use amethyst::{
+    core::transform::Transform,
+    ecs::prelude::{
+        Entities, Entity, Join, Read, ReadStorage, Resources, System, SystemData, Write,
+        WriteStorage,
+    },
+    shrev::{EventChannel, ReaderId},
+};
+
+use entities::Tile;
+use level::{FlowFields, LevelGrid, TileChanged};
+use systems::{Clear, Drill, Path};
+
+///
+/// This system keeps every `Path` up to date with the cave's layout.
+/// A `Path`, that crosses a `Tile` that changed, is searched again from the current position of its entity.
+/// If the destination cannot be reached anymore, the `Path` is dropped together with the order, the entity was walking for.
+/// The cached `FlowFields` are dropped on every change, so they are computed again when needed.
+///
+#[derive(Default)]
+pub struct RepathingSystem {
+    reader: Option<ReaderId<TileChanged>>,
+}
+
+impl<'a> System<'a> for RepathingSystem {
+    type SystemData = (
+        Entities<'a>,
+        Read<'a, EventChannel<TileChanged>>,
+        Read<'a, LevelGrid>,
+        Write<'a, FlowFields>,
+        ReadStorage<'a, Tile>,
+        ReadStorage<'a, Transform>,
+        WriteStorage<'a, Path>,
+        WriteStorage<'a, Drill>,
+        WriteStorage<'a, Clear>,
+    );
+
+    fn run(
+        &mut self,
+        (
+            entities,
+            tile_changes,
+            level_grid,
+            mut flow_fields,
+            tiles,
+            transforms,
+            mut paths,
+            mut drills,
+            mut clears,
+        ): Self::SystemData,
+    ) {
+        let changed: Vec<Entity> = tile_changes
+            .read(self.reader.as_mut().unwrap())
+            .map(|event| event.tile)
+            .collect();
+        if changed.is_empty() {
+            return;
+        }
+        flow_fields.invalidate();
+
+        let affected: Vec<Entity> = (&entities, &paths)
+            .join()
+            .filter(|(_, path)| {
+                path.iter().any(|point| {
+                    level_grid
+                        .get(point.x.round() as i32, point.y.round() as i32)
+                        .map_or(false, |tile| changed.contains(&tile))
+                })
+            })
+            .map(|(entity, _)| entity)
+            .collect();
+
+        for entity in affected {
+            let destination = *paths.get(entity).unwrap().last().unwrap();
+            let destination =
+                level_grid.get(destination.x.round() as i32, destination.y.round() as i32);
+            let (x, y) = LevelGrid::grid_position(transforms.get(entity).unwrap().translation());
+            let start = level_grid.get(x, y);
+
+            let path = match (start, destination) {
+                (Some(start), Some(destination)) => {
+                    level_grid.find_path(start, destination, &tiles, &transforms)
+                }
+                _ => None,
+            };
+
+            match path {
+                Some(path) => {
+                    paths.insert(entity, path).unwrap();
+                }
+                None => {
+                    paths.remove(entity);
+                    drills.remove(entity);
+                    clears.remove(entity);
+                    warn!("Destination unreachable: {:?} stops walking", entity);
+                }
+            }
+        }
+    }
+
+    fn setup(&mut self, res: &mut Resources) {
+        Self::SystemData::setup(res);
+        self.reader = Some(
+            res.fetch_mut::<EventChannel<TileChanged>>()
+                .register_reader(),
+        );
+    }
+}
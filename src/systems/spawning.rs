@@ -0,0 +1,131 @@
+use amethyst::{
+    core::{
+        nalgebra::Vector3,
+        timing::Time,
+        transform::{Parent, Transform},
+    },
+    ecs::prelude::{
+        Component, DenseVecStorage, Entity, Join, Read, ReadExpect, ReadStorage, System,
+        WriteStorage,
+    },
+    renderer::{ActiveCamera, Camera, ScreenDimensions},
+    ui::{UiText, UiTransform},
+};
+
+use eventhandling::to_window_space;
+
+/// The seconds it takes to teleport in a `RockRaider`
+pub const SPAWN_DURATION: f32 = 3.;
+
+/// The most `RockRaider`s, that can be in the level at once, including the queued ones
+pub const MAX_RAIDERS: usize = 10;
+
+/// How high above its `Base` the length of a `SpawnQueue` is shown
+const LABEL_HEIGHT: f32 = 1.;
+
+///
+/// This system counts down the `SpawnQueue` of every `Base` and shows its length above the `Base`.
+/// The `RockRaider`s, whose time has come, are spawned by the `LevelState`. See `Base::spawn_rock_raider`
+///
+pub struct SpawnSystem;
+
+impl<'a> System<'a> for SpawnSystem {
+    type SystemData = (
+        Read<'a, Time>,
+        Option<ReadExpect<'a, ActiveCamera>>,
+        ReadExpect<'a, ScreenDimensions>,
+        ReadStorage<'a, Camera>,
+        ReadStorage<'a, Parent>,
+        ReadStorage<'a, Transform>,
+        WriteStorage<'a, SpawnQueue>,
+        WriteStorage<'a, UiTransform>,
+        WriteStorage<'a, UiText>,
+    );
+
+    fn run(
+        &mut self,
+        (
+            time,
+            active_camera,
+            dimensions,
+            cameras,
+            parents,
+            transforms,
+            mut queues,
+            mut ui_transforms,
+            mut ui_texts,
+        ): Self::SystemData,
+    ) {
+        for queue in (&mut queues).join() {
+            if queue.queued > 0 {
+                queue.progress = (queue.progress + time.delta_seconds()).min(SPAWN_DURATION);
+            }
+        }
+
+        let active_camera = match active_camera {
+            Some(active_camera) => active_camera.entity,
+            None => return,
+        };
+        let (proj, view) = match (cameras.get(active_camera), transforms.get(active_camera)) {
+            (Some(camera), Some(transform)) => (camera.proj, transform.matrix()),
+            _ => return,
+        };
+
+        for (queue, parent, transform) in (&queues, &parents, &transforms).join() {
+            // the label is created by the `LevelState` and loaded in the background
+            let label = match queue.label {
+                Some(label) => label,
+                None => continue,
+            };
+            if let Some(ui_text) = ui_texts.get_mut(label) {
+                ui_text.text = if queue.queued > 0 {
+                    format!("Queued: {}", queue.queued)
+                } else {
+                    String::new()
+                };
+            }
+
+            // the `Base` is a child of its `Tile`
+            let position = transforms.get(parent.entity).unwrap().translation()
+                + transform.translation()
+                + Vector3::new(0., LABEL_HEIGHT, 0.);
+            let window_position = to_window_space(
+                &position,
+                (dimensions.width(), dimensions.height()),
+                proj,
+                view,
+            );
+            if let (Some(ui_transform), Some((x, y))) =
+                (ui_transforms.get_mut(label), window_position)
+            {
+                // the label is anchored at the top left corner of the window, where the ui's y axis points up
+                ui_transform.local_x = x;
+                ui_transform.local_y = -y;
+            }
+        }
+    }
+}
+
+/// The `RockRaider`s a `Base` is about to teleport in, one after another.
+#[derive(Debug, Default)]
+pub struct SpawnQueue {
+    /// The `RockRaider`s waiting to be teleported in
+    pub queued: u32,
+    /// The seconds the next `RockRaider` has been teleporting in for
+    pub progress: f32,
+    /// The grid position, that new `RockRaider`s walk to, if the player set one
+    pub rally_point: Option<(i32, i32)>,
+    /// The ui element, that shows the length of the queue
+    pub label: Option<Entity>,
+}
+
+impl SpawnQueue {
+    /// Whether the next `RockRaider` has finished teleporting in
+    pub fn is_ready(&self) -> bool {
+        self.queued > 0 && self.progress >= SPAWN_DURATION
+    }
+}
+
+impl Component for SpawnQueue {
+    type Storage = DenseVecStorage<Self>;
+}
@@ -0,0 +1,94 @@
+use amethyst::{
+    core::{
+        nalgebra::{Point2, Vector3},
+        transform::Transform,
+    },
+    ecs::prelude::{Entity, ReadStorage},
+};
+
+use entities::Tile;
+use level::LevelGrid;
+
+/// The distance between two `RockRaider`s, below which they start to push each other away
+const SEPARATION_RADIUS: f32 = 0.35;
+
+/// How fast overlapping `RockRaider`s are pushed apart. This is slower than walking, so a pushed `RockRaider` still reaches its waypoint.
+const SEPARATION_SPEED: f32 = 0.4;
+
+/// The places on a `Tile`, relative to its middle, where `RockRaider`s stand when they arrived
+const SLOTS: [(f32, f32); 4] = [(-0.25, -0.25), (0.25, 0.25), (-0.25, 0.25), (0.25, -0.25)];
+
+/// The distance to a slot, within which a `RockRaider` occupies it
+const SLOT_RADIUS: f32 = 0.15;
+
+/// Pushes a walking entity away from the other `RockRaider`s nearby, unless this would push it onto a `Tile` that cannot be walked on.
+///
+/// `raiders` are the positions of all `RockRaider`s, the entity itself may be part of it.
+pub fn keep_apart(
+    entity: Entity,
+    transform: &mut Transform,
+    raiders: &[(Entity, Vector3<f32>)],
+    level_grid: &LevelGrid,
+    tiles: &ReadStorage<Tile>,
+    delta_seconds: f32,
+) {
+    let push = separation(entity, transform.translation(), raiders);
+    let pushed = transform.translation() + push * SEPARATION_SPEED * delta_seconds;
+
+    let (x, y) = LevelGrid::grid_position(&pushed);
+    if level_grid.is_walkable(x, y, tiles) {
+        transform.set_position(pushed);
+    }
+}
+
+/// Sums up the directions away from every other `RockRaider` within `SEPARATION_RADIUS`. The nearer the other one is, the stronger it pushes.
+fn separation(
+    entity: Entity,
+    position: &Vector3<f32>,
+    raiders: &[(Entity, Vector3<f32>)],
+) -> Vector3<f32> {
+    let mut push = Vector3::zeros();
+    for (other, other_position) in raiders {
+        if *other == entity {
+            continue;
+        }
+
+        let mut away = position - other_position;
+        away.y = 0.;
+        let distance = away.norm();
+        if distance >= SEPARATION_RADIUS {
+            continue;
+        }
+
+        let direction = if distance > 0.001 {
+            away / distance
+        } else {
+            // both stand on the very same spot, so they split up in opposite directions
+            if entity.id() < other.id() {
+                Vector3::x()
+            } else {
+                -Vector3::x()
+            }
+        };
+        push += direction * (1. - distance / SEPARATION_RADIUS);
+    }
+    push
+}
+
+/// Returns the slot on the `Tile` at (x, y), that the fewest of the `standing` `RockRaider`s occupy.
+///
+/// This way, several `RockRaider`s can stand on the same `Tile` without overlapping.
+pub fn free_slot(x: i32, y: i32, standing: &[(Entity, Vector3<f32>)]) -> Point2<f32> {
+    SLOTS
+        .iter()
+        .map(|(offset_x, offset_y)| Point2::new(x as f32 + offset_x, y as f32 + offset_y))
+        .min_by_key(|slot| {
+            standing
+                .iter()
+                .filter(|(_, position)| {
+                    (Point2::new(position.x, position.z) - slot).norm() < SLOT_RADIUS
+                })
+                .count()
+        })
+        .unwrap()
+}
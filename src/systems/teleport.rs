@@ -0,0 +1,70 @@
+use amethyst::ecs::prelude::{
+    Component, Entity, Join, NullStorage, ReadStorage, System, WriteStorage,
+};
+
+use entities::{buildings::Base, RockRaider, RockRaiderRemovalStorages};
+use level::LevelGrid;
+use systems::Path;
+
+///
+/// This system sends the `RockRaider`s, that were ordered to `TeleportOut`, back through the `Base`, as soon as they walked up to it.
+/// They leave the level, so they do not breathe any oxygen anymore. See `RockRaider::remove`
+///
+pub struct TeleportSystem;
+
+impl<'a> System<'a> for TeleportSystem {
+    type SystemData = (
+        ReadStorage<'a, Base>,
+        ReadStorage<'a, Path>,
+        WriteStorage<'a, TeleportOut>,
+        RockRaiderRemovalStorages<'a>,
+    );
+
+    fn run(&mut self, (bases, paths, mut teleports, mut storages): Self::SystemData) {
+        let (arrived, base_position) = {
+            let (ref entities, _, _, _, _, _, ref parents, ref transforms, _) = storages;
+
+            // the grid position of the `Tile` the `Base` is built on
+            let base_position = (&bases, parents)
+                .join()
+                .map(|(_, parent)| {
+                    LevelGrid::grid_position(transforms.get(parent.entity).unwrap().translation())
+                })
+                .next();
+
+            // only entities without a `Path` have arrived
+            let arrived: Vec<(Entity, (i32, i32))> = (&**entities, &teleports, transforms, !&paths)
+                .join()
+                .map(|(entity, _, transform, _)| {
+                    (entity, LevelGrid::grid_position(transform.translation()))
+                })
+                .collect();
+            (arrived, base_position)
+        };
+
+        for (entity, (x, y)) in arrived {
+            teleports.remove(entity);
+
+            let at_base = base_position.map_or(false, |(base_x, base_y)| {
+                (base_x - x).abs() + (base_y - y).abs() <= 1
+            });
+            if at_base {
+                info!("{:?} teleported out", entity);
+                RockRaider::remove(entity, &mut storages);
+            } else {
+                warn!(
+                    "{:?} did not reach the Base and cannot teleport out",
+                    entity
+                );
+            }
+        }
+    }
+}
+
+/// The order to walk to the `Base` and teleport out of the level
+#[derive(Default)]
+pub struct TeleportOut;
+
+impl Component for TeleportOut {
+    type Storage = NullStorage<Self>;
+}
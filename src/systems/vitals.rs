@@ -0,0 +1,86 @@
+use amethyst::{
+    ecs::prelude::{
+        Component, DenseVecStorage, Entity, Read, ReadStorage, Resources, System, SystemData,
+        WriteStorage,
+    },
+    shrev::{EventChannel, ReaderId},
+};
+
+use entities::{RockRaider, RockRaiderRemovalStorages};
+
+///
+/// This system applies every `Damage` to the `Health` of its target.
+/// Whatever has no `Health` left dies and is removed from the level. See `RockRaider::remove`
+///
+#[derive(Default)]
+pub struct VitalsSystem {
+    reader: Option<ReaderId<Damage>>,
+}
+
+impl<'a> System<'a> for VitalsSystem {
+    type SystemData = (
+        Read<'a, EventChannel<Damage>>,
+        WriteStorage<'a, Health>,
+        ReadStorage<'a, RockRaider>,
+        RockRaiderRemovalStorages<'a>,
+    );
+
+    fn run(&mut self, (damages, mut healths, rock_raiders, mut storages): Self::SystemData) {
+        let mut dead: Vec<Entity> = vec![];
+        for damage in damages.read(self.reader.as_mut().unwrap()) {
+            if let Some(health) = healths.get_mut(damage.target) {
+                // it may have been hurt twice in the same frame
+                if health.current > 0. {
+                    health.damage(damage.amount);
+                    if health.current <= 0. {
+                        dead.push(damage.target);
+                    }
+                }
+            }
+        }
+
+        for entity in dead {
+            if rock_raiders.get(entity).is_some() {
+                info!("{:?} died", entity);
+                RockRaider::remove(entity, &mut storages);
+            } else {
+                let (ref entities, ..) = storages;
+                entities.delete(entity).unwrap();
+            }
+        }
+    }
+
+    fn setup(&mut self, res: &mut Resources) {
+        Self::SystemData::setup(res);
+        self.reader = Some(res.fetch_mut::<EventChannel<Damage>>().register_reader());
+    }
+}
+
+/// An event, that is published to the `EventChannel<Damage>` whenever something hurts an entity with `Health`.
+#[derive(Clone, Copy, Debug)]
+pub struct Damage {
+    pub target: Entity,
+    pub amount: f32,
+}
+
+/// The health of something, that can be hurt. It never drops below zero.
+#[derive(Debug)]
+pub struct Health {
+    pub max: f32,
+    pub current: f32,
+}
+
+impl Health {
+    pub fn new(max: f32) -> Self {
+        Health { max, current: max }
+    }
+
+    /// Do not call this directly, but publish a `Damage` event, so that death is handled by the `VitalsSystem`.
+    pub fn damage(&mut self, amount: f32) {
+        self.current = (self.current - amount).max(0.);
+    }
+}
+
+impl Component for Health {
+    type Storage = DenseVecStorage<Self>;
+}